@@ -0,0 +1,102 @@
+#![feature(test)]
+
+extern crate spectra;
+extern crate test;
+
+use spectra::spline::*;
+use test::{Bencher, black_box};
+
+const NB_KEYS: usize = 10000;
+const NB_SAMPLES: usize = 100;
+
+fn big_spline() -> Spline<f32> {
+  Spline::from_keys((0..NB_KEYS).map(|i| {
+    let t = i as f32;
+    Key::new(t, t.sin(), Interpolation::Linear)
+  }).collect())
+}
+
+// Sampling times spread over the whole spline, as when seeking.
+fn spread_times() -> Vec<Time> {
+  (0..NB_SAMPLES).map(|i| ((i * 7919) % (NB_KEYS - 1)) as f32 + 0.5).collect()
+}
+
+// Sampling times of a playback at 60 FPS over the end of the spline.
+fn playback_times() -> Vec<Time> {
+  let start = (NB_KEYS - 100) as f32;
+  (0..NB_SAMPLES).map(|i| start + i as f32 / 60.).collect()
+}
+
+// The lookup used before the binary search was introduced: a linear walk from the first key.
+fn linear_search_lower_cp<T>(cps: &[Key<T>], t: Time) -> Option<usize> {
+  let mut i = 0;
+  let len = cps.len();
+
+  if len < 2 {
+    return None;
+  }
+
+  loop {
+    let cp = &cps[i];
+    let cp1 = &cps[i+1];
+
+    if t >= cp1.t {
+      if i >= len - 2 {
+        return None;
+      }
+
+      i += 1;
+    } else if t < cp.t {
+      if i == 0 {
+        return None;
+      }
+
+      i -= 1;
+    } else {
+      break;
+    }
+  }
+
+  Some(i)
+}
+
+#[bench]
+fn linear_search_spread(b: &mut Bencher) {
+  let spline = big_spline();
+  let times = spread_times();
+
+  b.iter(|| for &t in &times { black_box(linear_search_lower_cp(spline.keys(), t)); });
+}
+
+#[bench]
+fn linear_search_playback(b: &mut Bencher) {
+  let spline = big_spline();
+  let times = playback_times();
+
+  b.iter(|| for &t in &times { black_box(linear_search_lower_cp(spline.keys(), t)); });
+}
+
+#[bench]
+fn sample_spread(b: &mut Bencher) {
+  let spline = big_spline();
+  let times = spread_times();
+
+  b.iter(|| for &t in &times { black_box(spline.sample(t)); });
+}
+
+#[bench]
+fn sample_playback(b: &mut Bencher) {
+  let spline = big_spline();
+  let times = playback_times();
+
+  b.iter(|| for &t in &times { black_box(spline.sample(t)); });
+}
+
+#[bench]
+fn cursor_playback(b: &mut Bencher) {
+  let spline = big_spline();
+  let times = playback_times();
+  let mut cursor = SplineCursor::new();
+
+  b.iter(|| for &t in &times { black_box(cursor.sample(&spline, t)); });
+}
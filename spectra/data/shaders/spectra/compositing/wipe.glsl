@@ -0,0 +1,32 @@
+#vs
+
+vec2[4] CO = vec2[](
+  vec2( 1., -1.),
+  vec2( 1.,  1.),
+  vec2(-1., -1.),
+  vec2(-1.,  1.)
+);
+
+void main() {
+  gl_Position = vec4(CO[gl_VertexID], 0., 1.);
+}
+
+#fs
+
+uniform sampler2D from;
+uniform sampler2D to;
+uniform float progress;
+uniform vec2 direction;
+uniform vec2 resolution;
+
+out vec4 frag;
+
+void main() {
+  ivec2 co = ivec2(gl_FragCoord.xy);
+  vec2 d = normalize(direction);
+
+  // position of the fragment along the direction of the wipe, from 0 to 1 across the screen
+  float x = dot(gl_FragCoord.xy / resolution - .5, d) / (abs(d.x) + abs(d.y)) + .5;
+
+  frag = x < progress ? texelFetch(to, co, 0) : texelFetch(from, co, 0);
+}
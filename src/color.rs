@@ -1,4 +1,237 @@
+//! Color types and color spaces.
+//!
+//! `RGB` and `RGBA` are *linear* colors – the ones you should feed shaders with. The other types
+//! represent the same colors in other spaces and convert from and to linear RGB.
+
 use linear::{Vector3, Vector4};
 
+/// Linear RGB color.
 pub type RGB = Vector3<f32>;
+/// Linear RGB color with alpha.
 pub type RGBA = Vector4<f32>;
+
+/// Color space in which colors can be blended.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ColorSpace {
+  /// Linear RGB; physically correct when mixing lights.
+  #[serde(rename = "linear")]
+  Linear,
+  /// Gamma-encoded sRGB; matches most image editors.
+  #[serde(rename = "srgb")]
+  SRGB,
+  /// Hue, saturation and value; hues turn around the color wheel along the shortest path.
+  #[serde(rename = "hsv")]
+  HSV,
+  /// Oklab; perceptually uniform, keeps gradients between saturated colors vivid.
+  #[serde(rename = "oklab")]
+  Oklab
+}
+
+impl Default for ColorSpace {
+  /// `ColorSpace::Linear` is the default.
+  fn default() -> Self {
+    ColorSpace::Linear
+  }
+}
+
+/// Gamma-encoded sRGB color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SRGB {
+  pub r: f32,
+  pub g: f32,
+  pub b: f32
+}
+
+impl SRGB {
+  pub fn new(r: f32, g: f32, b: f32) -> Self {
+    SRGB {
+      r: r,
+      g: g,
+      b: b
+    }
+  }
+
+  /// Encode a linear color.
+  pub fn from_linear(rgb: RGB) -> Self {
+    SRGB::new(srgb_encode(rgb.x), srgb_encode(rgb.y), srgb_encode(rgb.z))
+  }
+
+  /// Decode into a linear color.
+  pub fn to_linear(&self) -> RGB {
+    RGB::new(srgb_decode(self.r), srgb_decode(self.g), srgb_decode(self.b))
+  }
+}
+
+fn srgb_encode(c: f32) -> f32 {
+  if c <= 0.0031308 {
+    c * 12.92
+  } else {
+    1.055 * c.powf(1. / 2.4) - 0.055
+  }
+}
+
+fn srgb_decode(c: f32) -> f32 {
+  if c <= 0.04045 {
+    c / 12.92
+  } else {
+    ((c + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+/// HSV color, computed out of the sRGB-encoded components.
+///
+/// The hue is in degrees, in `[0; 360)`; saturation and value are in `[0; 1]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HSV {
+  pub h: f32,
+  pub s: f32,
+  pub v: f32
+}
+
+impl HSV {
+  pub fn new(h: f32, s: f32, v: f32) -> Self {
+    HSV {
+      h: h,
+      s: s,
+      v: v
+    }
+  }
+
+  pub fn from_linear(rgb: RGB) -> Self {
+    let SRGB { r, g, b } = SRGB::from_linear(rgb);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let h = if delta == 0. {
+      0.
+    } else if max == r {
+      60. * ((g - b) / delta)
+    } else if max == g {
+      60. * ((b - r) / delta + 2.)
+    } else {
+      60. * ((r - g) / delta + 4.)
+    };
+    let s = if max > 0. { delta / max } else { 0. };
+
+    HSV::new(wrap_hue(h), s, max)
+  }
+
+  pub fn to_linear(&self) -> RGB {
+    let c = self.v * self.s;
+    let h = wrap_hue(self.h) / 60.;
+    let x = c * (1. - (h % 2. - 1.).abs());
+    let m = self.v - c;
+
+    let (r, g, b) = match h as u32 {
+      0 => (c, x, 0.),
+      1 => (x, c, 0.),
+      2 => (0., c, x),
+      3 => (0., x, c),
+      4 => (x, 0., c),
+      _ => (c, 0., x)
+    };
+
+    SRGB::new(r + m, g + m, b + m).to_linear()
+  }
+}
+
+// Bring a hue in [0; 360).
+fn wrap_hue(h: f32) -> f32 {
+  let h = h % 360.;
+
+  if h < 0. { h + 360. } else { h }
+}
+
+/// Oklab color.
+///
+/// `l` is the perceived lightness, `a` and `b` the green-red and blue-yellow axes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklab {
+  pub l: f32,
+  pub a: f32,
+  pub b: f32
+}
+
+impl Oklab {
+  pub fn new(l: f32, a: f32, b: f32) -> Self {
+    Oklab {
+      l: l,
+      a: a,
+      b: b
+    }
+  }
+
+  pub fn from_linear(rgb: RGB) -> Self {
+    let l = (0.4122214708 * rgb.x + 0.5363325363 * rgb.y + 0.0514459929 * rgb.z).cbrt();
+    let m = (0.2119034982 * rgb.x + 0.6806995451 * rgb.y + 0.1073969566 * rgb.z).cbrt();
+    let s = (0.0883024619 * rgb.x + 0.2817188376 * rgb.y + 0.6299787005 * rgb.z).cbrt();
+
+    Oklab::new(
+      0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+      1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+      0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s
+    )
+  }
+
+  pub fn to_linear(&self) -> RGB {
+    let l = self.l + 0.3963377774 * self.a + 0.2158037573 * self.b;
+    let m = self.l - 0.1055613458 * self.a - 0.0638541728 * self.b;
+    let s = self.l - 0.0894841775 * self.a - 1.2914855480 * self.b;
+    let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+
+    RGB::new(
+      4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+      -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+      -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s
+    )
+  }
+}
+
+/// Blend two linear colors in a given color space.
+pub fn blend_rgb(a: RGB, b: RGB, t: f32, space: ColorSpace) -> RGB {
+  match space {
+    ColorSpace::Linear => a + (b - a) * t,
+    ColorSpace::SRGB => {
+      let (a, b) = (SRGB::from_linear(a), SRGB::from_linear(b));
+      SRGB::new(lerp(a.r, b.r, t), lerp(a.g, b.g, t), lerp(a.b, b.b, t)).to_linear()
+    },
+    ColorSpace::HSV => {
+      let (a, b) = (HSV::from_linear(a), HSV::from_linear(b));
+
+      // achromatic colors have no meaningful hue: use the one of the other color
+      let (ha, hb) = if a.s == 0. {
+        (b.h, b.h)
+      } else if b.s == 0. {
+        (a.h, a.h)
+      } else {
+        (a.h, b.h)
+      };
+      let mut dh = hb - ha;
+
+      if dh > 180. {
+        dh -= 360.;
+      } else if dh < -180. {
+        dh += 360.;
+      }
+
+      HSV::new(wrap_hue(ha + dh * t), lerp(a.s, b.s, t), lerp(a.v, b.v, t)).to_linear()
+    },
+    ColorSpace::Oklab => {
+      let (a, b) = (Oklab::from_linear(a), Oklab::from_linear(b));
+      Oklab::new(lerp(a.l, b.l, t), lerp(a.a, b.a, t), lerp(a.b, b.b, t)).to_linear()
+    }
+  }
+}
+
+/// Blend two linear colors with alpha in a given color space. The alpha channel is always blended
+/// linearly.
+pub fn blend_rgba(a: RGBA, b: RGBA, t: f32, space: ColorSpace) -> RGBA {
+  let rgb = blend_rgb(RGB::new(a.x, a.y, a.z), RGB::new(b.x, b.y, b.z), t, space);
+
+  RGBA::new(rgb.x, rgb.y, rgb.z, lerp(a.w, b.w, t))
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+  a + (b - a) * t
+}
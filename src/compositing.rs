@@ -53,6 +53,12 @@ pub enum Node<'a> {
   /// Composite nodes are used to blend two compositing nodes according to a given `Equation` and
   /// two blending `Factor`s for source and destination, respectively.
   Composite(Box<Node<'a>>, Box<Node<'a>>, RGBA, Equation, Factor, Factor),
+  /// Wipe node.
+  ///
+  /// The right node progressively covers the left node along a line moving across the screen. The
+  /// `f32` is the progress of the wipe, from `0` (left node only) to `1` (right node only), and the
+  /// `[f32; 2]` is the direction the line moves in.
+  Wipe(Box<Node<'a>>, Box<Node<'a>>, f32, [f32; 2]),
   /// Simple fullscreen effect.
   ///
   /// Such a node is used to apply a user-defined shader on a fullscreen quad. The shader should
@@ -77,6 +83,11 @@ impl<'a> Node<'a> {
   pub fn over(self, rhs: Self) -> Self {
     rhs.compose_with(self, RGBA::new(0., 0., 0., 0.), Equation::Additive, Factor::SrcAlpha, Factor::SrcAlphaComplement)
   }
+
+  /// Wipe this node with another one. See `Node::Wipe`.
+  pub fn wipe(self, rhs: Self, progress: f32, direction: [f32; 2]) -> Self {
+    Node::Wipe(Box::new(self), Box::new(rhs), progress, direction)
+  }
 }
 
 impl<'a> From<RenderLayer<'a>> for Node<'a> {
@@ -141,6 +152,8 @@ pub struct Compositor {
   compose_program: Res<Program>,
   // program used to render textures scaled
   texture_program: Res<Program>,
+  // program used to wipe a node with another
+  wipe_program: Res<Program>,
   // attributeless fullscreen quad for compositing
   quad: Tess
 }
@@ -150,6 +163,12 @@ const FORWARD_SOURCE: &'static Uniform<Unit> = &Uniform::new(0);
 const TEXTURE_SOURCE: &'static Uniform<Unit> = &Uniform::new(0);
 const TEXTURE_SCALE: &'static Uniform<[f32; 2]> = &Uniform::new(1);
 
+const WIPE_FROM: &'static Uniform<Unit> = &Uniform::new(0);
+const WIPE_TO: &'static Uniform<Unit> = &Uniform::new(1);
+const WIPE_PROGRESS: &'static Uniform<f32> = &Uniform::new(2);
+const WIPE_DIRECTION: &'static Uniform<[f32; 2]> = &Uniform::new(3);
+const WIPE_RESOLUTION: &'static Uniform<[f32; 2]> = &Uniform::new(4);
+
 impl Compositor {
   pub fn new(w: u32, h: u32, cache: &mut ResCache) -> Self {
     Compositor {
@@ -162,6 +181,13 @@ impl Compositor {
         TEXTURE_SOURCE.sem("source"),
         TEXTURE_SCALE.sem("scale")
       ]).unwrap(),
+      wipe_program: cache.get("spectra/compositing/wipe.glsl", vec![
+        WIPE_FROM.sem("from"),
+        WIPE_TO.sem("to"),
+        WIPE_PROGRESS.sem("progress"),
+        WIPE_DIRECTION.sem("direction"),
+        WIPE_RESOLUTION.sem("resolution")
+      ]).unwrap(),
       quad: Tess::attributeless(Mode::TriangleStrip, 4)
     }
   }
@@ -221,6 +247,7 @@ impl Compositor {
       Node::Texture(texture, scale) => self.texturize(texture, scale),
       Node::Color(color) => self.colorize(color),
       Node::Composite(left, right, clear_color, eq, src_fct, dst_fct) => self.composite(*left, *right, clear_color, eq, src_fct, dst_fct),
+      Node::Wipe(left, right, progress, direction) => self.wipe(*left, *right, progress, direction),
       Node::FullscreenEffect(program) => self.fullscreen_effect(program)
     }
   }
@@ -310,6 +337,48 @@ impl Compositor {
     fb_index
   }
 
+  fn wipe(&mut self, left: Node, right: Node, progress: f32, direction: [f32; 2]) -> usize {
+    let left_index = self.treat_node(left);
+    let right_index = self.treat_node(right);
+
+    let fb_index = self.pull_framebuffer();
+
+    {
+      let fb = &self.framebuffers[fb_index];
+
+      let left_fb = &self.framebuffers[left_index];
+      let right_fb = &self.framebuffers[right_index];
+
+      let texture_set = &[
+        &*left_fb.color_slot,
+        &*right_fb.color_slot
+      ];
+      let wipe_program = self.wipe_program.borrow();
+      let tess_render = TessRender::from(&self.quad);
+      let resolution = [self.w as f32, self.h as f32];
+
+      Pipeline::new(fb, [0., 0., 0., 1.], texture_set, &[]).enter(|shd_gate| {
+        shd_gate.new(&wipe_program, &[], &[], &[]).enter(|rdr_gate| {
+          rdr_gate.new(None, false, &[], &[], &[]).enter(|tess_gate| {
+            let uniforms = [
+              WIPE_FROM.alter(Unit::new(0)),
+              WIPE_TO.alter(Unit::new(1)),
+              WIPE_PROGRESS.alter(progress),
+              WIPE_DIRECTION.alter(direction),
+              WIPE_RESOLUTION.alter(resolution)
+            ];
+            tess_gate.render(tess_render, &uniforms, &[], &[]);
+          });
+        });
+      });
+    }
+
+    self.dispose_framebuffer(left_index);
+    self.dispose_framebuffer(right_index);
+
+    fb_index
+  }
+
   fn fullscreen_effect(&mut self, program: &Program) -> usize {
     let fb_index = self.pull_framebuffer();
     let fb = &self.framebuffers[fb_index];
@@ -0,0 +1,179 @@
+//! CMX3600 EDL interchange.
+//!
+//! An EDL describes a single video track as a list of events. Every cut of a `TrackManifest` is an
+//! event: its *input* and *output times* are the source timecodes and its placement the record
+//! timecodes. The name of the clip is kept in a `* FROM CLIP NAME:` comment and reels are named
+//! after clips. Speed and reverse playback use `M2` motion effects; time remapping and transitions
+//! are not exported.
+//!
+//! Timecodes are non-drop-frame: at NTSC rates, they count the nominal number of frames per second
+//! – 24 at 23.976 fps. Record timecodes are offset by the start of the program, usually
+//! `01:00:00:00`.
+
+use resource::{LoadError, Result, SaveError};
+use edit::{CutManifest, FrameRate, Time, TrackManifest};
+use tempo::TimeSpec;
+
+/// Export a track as an EDL.
+///
+/// The placement of the cuts is offset by `record_start`, the timecode at which the program
+/// starts. Cuts in musical time are left out – resolve them first with
+/// `TimelineManifest::resolved` – as well as cuts with a non-positive speed.
+///
+/// Fail if the frame rate is null or `record_start` isn’t a valid timecode.
+pub fn to_edl(track: &TrackManifest, title: &str, rate: FrameRate, record_start: &str) -> ::std::result::Result<String, SaveError> {
+  if !rate.is_valid() {
+    return Err(SaveError::SerializationFailed(format!("invalid frame rate: {}/{}", rate.num, rate.den)));
+  }
+
+  let base = rate.timecode_base();
+  let start = from_timecode(record_start, base).map_err(|_| SaveError::SerializationFailed(format!("invalid record start: {:?}", record_start)))?;
+  let mut edl = format!("TITLE: {}\nFCM: NON-DROP FRAME\n", title);
+  let mut event = 1;
+
+  for cut in &track.cuts {
+    let (in_time, out_time, inst_time) = match (cut.in_time.secs(), cut.out_time.secs(), cut.inst_time.secs()) {
+      (Some(in_time), Some(out_time), Some(inst_time)) => (in_time, out_time, inst_time),
+      _ => {
+        warn!("the cut of {:?} is in musical time and cannot be exported", cut.clip);
+        continue;
+      }
+    };
+
+    if cut.speed <= 0. {
+      warn!("the cut of {:?} has a non-positive speed and cannot be exported", cut.clip);
+      continue;
+    }
+
+    // like the record timecodes, the source out timecode is based on a normal speed
+    let dur = (out_time - in_time) / cut.speed;
+    let reel = reel_name(&cut.clip);
+    let src_in = to_timecode(to_frames(in_time, rate), base);
+    let src_out = to_timecode(to_frames(in_time + dur, rate), base);
+    let rec_in = to_timecode(start + to_frames(inst_time, rate), base);
+    let rec_out = to_timecode(start + to_frames(inst_time + dur, rate), base);
+
+    edl.push_str(&format!("\n{:03}  {:<8} V     C        {} {} {} {}\n", event, reel, src_in, src_out, rec_in, rec_out));
+
+    if cut.speed != 1. || cut.reverse {
+      let speed = if cut.reverse { -cut.speed } else { cut.speed };
+      edl.push_str(&format!("M2   {:<8}       {:05.1}                {}\n", reel, speed * rate.fps(), src_in));
+    }
+
+    edl.push_str(&format!("* FROM CLIP NAME: {}\n", cut.clip));
+
+    event += 1;
+  }
+
+  Ok(edl)
+}
+
+/// Import a track from an EDL.
+///
+/// Only events with video are imported – their track field contains a `V`, as in `V`, `V2` or
+/// `AA/V`, or is `B` for both audio and video; other events are skipped with a warning. Clips are
+/// named after the `* FROM CLIP NAME:` comments, or after reels if missing.
+///
+/// The placement of the cuts is taken relative to `record_start`, the timecode at which the program
+/// starts. Fail if the frame rate is null, `record_start` isn’t a valid timecode or an event is
+/// recorded before it.
+pub fn from_edl(edl: &str, rate: FrameRate, record_start: &str) -> Result<TrackManifest> {
+  if !rate.is_valid() {
+    return Err(LoadError::ParseFailed(format!("invalid frame rate: {}/{}", rate.num, rate.den)));
+  }
+
+  let base = rate.timecode_base();
+  let start = from_timecode(record_start, base)?;
+  let mut cuts: Vec<CutManifest> = Vec::new();
+  // whether the last event line was a video event; comments and effects apply to it
+  let mut last_is_video = false;
+
+  for line in edl.lines().map(str::trim) {
+    let tokens: Vec<_> = line.split_whitespace().collect();
+
+    if line.starts_with("* FROM CLIP NAME:") {
+      if let (true, Some(cut)) = (last_is_video, cuts.last_mut()) {
+        cut.clip = line["* FROM CLIP NAME:".len()..].trim().to_owned();
+      }
+    } else if tokens.first() == Some(&"M2") {
+      if let (true, Some(cut)) = (last_is_video, cuts.last_mut()) {
+        let speed = tokens.get(2).and_then(|x| x.parse::<Time>().ok()).ok_or_else(|| LoadError::ParseFailed(format!("invalid motion effect: {:?}", line)))? / rate.fps();
+
+        if speed == 0. {
+          return Err(LoadError::ParseFailed(format!("freeze frames are not supported: {:?}", line)));
+        }
+
+        let in_time = cut.in_time.secs().unwrap_or(0.);
+        let dur = cut.out_time.secs().unwrap_or(0.) - in_time;
+
+        // the source out timecode of the event is based on a normal speed; scale it back
+        cut.speed = speed.abs();
+        cut.reverse = speed < 0.;
+        cut.out_time = TimeSpec::Secs(in_time + dur * cut.speed);
+      }
+    } else if tokens.len() >= 8 && tokens[0].chars().all(|c| c.is_digit(10)) {
+      last_is_video = tokens[2].contains('V') || tokens[2] == "B";
+
+      if !last_is_video {
+        warn!("the event {} has no video and is skipped", tokens[0]);
+        continue;
+      }
+
+      let timecodes = &tokens[tokens.len() - 4..];
+      let in_time = from_timecode(timecodes[0], base)? as Time / rate.fps();
+      let out_time = from_timecode(timecodes[1], base)? as Time / rate.fps();
+      let rec_in = from_timecode(timecodes[2], base)?;
+
+      if rec_in < start {
+        return Err(LoadError::ParseFailed(format!("the event {} is recorded before the record start {:?}", tokens[0], record_start)));
+      }
+
+      let inst_time = (rec_in - start) as Time / rate.fps();
+
+      cuts.push(CutManifest {
+        in_time: TimeSpec::Secs(in_time),
+        out_time: TimeSpec::Secs(out_time),
+        inst_time: TimeSpec::Secs(inst_time),
+        clip: tokens[1].to_owned(),
+        speed: 1.,
+        reverse: false,
+        remap: None
+      });
+    }
+  }
+
+  Ok(TrackManifest {
+    cuts: cuts,
+    muted: false,
+    solo: false
+  })
+}
+
+// Reel names are limited to 8 characters, without spaces.
+fn reel_name(clip: &str) -> String {
+  clip.chars().filter(|c| !c.is_whitespace()).take(8).collect()
+}
+
+// Number of frames elapsed at a given time.
+fn to_frames(t: Time, rate: FrameRate) -> u64 {
+  (t * rate.fps()).round().max(0.) as u64
+}
+
+// Timecode of a frame, `base` being the number of frames in a second of timecode.
+fn to_timecode(frames: u64, base: u32) -> String {
+  let base = base as u64;
+  let secs = frames / base;
+
+  format!("{:02}:{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60, frames % base)
+}
+
+// Frame of a timecode, `base` being the number of frames in a second of timecode.
+fn from_timecode(timecode: &str, base: u32) -> Result<u64> {
+  let fields: Vec<u64> = timecode.split(|c| c == ':' || c == ';').filter_map(|x| x.parse().ok()).collect();
+
+  if fields.len() != 4 {
+    return Err(LoadError::ParseFailed(format!("invalid timecode: {:?}", timecode)));
+  }
+
+  Ok((fields[0] * 3600 + fields[1] * 60 + fields[2]) * base as u64 + fields[3])
+}
@@ -0,0 +1,1475 @@
+use serde::{Deserializer, Serializer};
+use serde_json::{Value, from_reader, to_writer_pretty};
+use std::collections::HashMap;
+use std::fs::File;
+use std::iter::once;
+use std::path::Path;
+use std::rc::Rc;
+
+use color::RGBA;
+use compositing::Node;
+use resource::{Load, LoadError, Result, ResCache, Save, SaveError};
+use spline::{Spline, deserialize_spline, serialize_spline};
+use tempo::{TempoMap, TimeSpec, TimeUnit};
+
+pub mod edl;
+pub mod otio;
+
+pub use self::edl::{from_edl, to_edl};
+pub use self::otio::{from_otio, to_otio};
+
+/// Time.
+pub type Time = f64;
+
+/// Frame rate of an interchange format, as a ratio of frames per second so that NTSC rates are
+/// exact – `FrameRate::new(24000, 1001)` is 23.976 fps.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FrameRate {
+  pub num: u32,
+  pub den: u32
+}
+
+impl FrameRate {
+  pub fn new(num: u32, den: u32) -> Self {
+    FrameRate {
+      num: num,
+      den: den
+    }
+  }
+
+  /// Frames per second.
+  pub fn fps(&self) -> Time {
+    self.num as Time / self.den as Time
+  }
+
+  /// Number of frames in a second of timecode: the frame rate rounded to the nearest integer.
+  pub fn timecode_base(&self) -> u32 {
+    self.fps().round() as u32
+  }
+
+  /// Whether the frame rate has at least one frame per second of timecode.
+  pub fn is_valid(&self) -> bool {
+    self.den > 0 && self.timecode_base() > 0
+  }
+}
+
+impl From<u32> for FrameRate {
+  fn from(fps: u32) -> Self {
+    FrameRate::new(fps, 1)
+  }
+}
+
+/// Context in which a clip is played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClipContext {
+  /// Time of the timeline playing the cut.
+  pub global: Time,
+  /// Time in the clip, between the *input* and *output times* of the cut, accounting for its speed,
+  /// direction and time remapping.
+  pub local: Time,
+  /// Normalized progress in the cut, from `0` to `1`.
+  pub progress: Time,
+  /// Duration of the cut.
+  pub dur: Time
+}
+
+pub struct Clip<'a, 'b> where 'a: 'b {
+  gen_node: Box<Fn(ClipContext) -> Node<'a> + 'b>
+}
+
+impl<'a, 'b> Clip<'a, 'b> {
+  pub fn new<F>(f: F) -> Self where F: 'b + Fn(ClipContext) -> Node<'a> {
+    Clip {
+      gen_node: Box::new(f)
+    }
+  }
+
+  /// Create a clip that plays a whole timeline.
+  ///
+  /// The timeline is played at the local time of the clip. If it has nothing to show at that time
+  /// – or cannot fold its nodes – the clip is transparent.
+  pub fn from_timeline<'x>(timeline: Timeline<'a, 'x, 'b>) -> Self where 'a: 'x, 'x: 'b {
+    Self::new(move |ctx| {
+      match timeline.play(ctx.local) {
+        Played::Resolved(node) => node,
+        _ => RGBA::new(0., 0., 0., 0.).into()
+      }
+    })
+  }
+}
+
+/// A cut is an object that slices a `Clip` at an *input time* and *output time*. It is instantiated
+/// in a `Track` at a given *instance time*.
+///
+/// By default, the clip plays forward at normal speed. The speed – see `Cut::set_speed` – scales
+/// the playback – and the duration of the cut accordingly – and `reverse` plays the clip
+/// backwards, from its *output time* to its *input time*. `remap` is an optional spline mapping
+/// the normalized progress in the cut to the normalized position in the clip, used for speed ramps
+/// and freeze frames.
+///
+/// `name` is the name of the clip, if known – cuts built from a `TimelineManifest` have it. The
+/// `*_unit` fields give the units the times were written in; `Timeline::to_manifest` writes them
+/// back in the same units when the timeline has a tempo map.
+#[derive(Clone)]
+pub struct Cut<'a, 'b, 'c> where 'a: 'b, 'b: 'c {
+  pub in_time: Time,
+  pub out_time: Time,
+  pub inst_time: Time,
+  pub clip: &'c Clip<'a, 'b>,
+  pub name: Option<String>,
+  // strictly positive
+  speed: Time,
+  pub reverse: bool,
+  pub remap: Option<Spline<f32>>,
+  pub in_unit: TimeUnit,
+  pub out_unit: TimeUnit,
+  pub inst_unit: TimeUnit
+}
+
+impl<'a, 'b, 'c> Cut<'a, 'b, 'c> where 'a: 'b, 'b: 'c {
+  pub fn new(in_time: Time, out_time: Time, inst_time: Time, clip: &'c Clip<'a, 'b>) -> Self {
+    assert!(in_time <= out_time);
+
+    Cut {
+      in_time: in_time,
+      out_time: out_time,
+      inst_time: inst_time,
+      clip: clip,
+      name: None,
+      speed: 1.,
+      reverse: false,
+      remap: None,
+      in_unit: TimeUnit::Secs,
+      out_unit: TimeUnit::Secs,
+      inst_unit: TimeUnit::Secs
+    }
+  }
+
+  /// Playback speed of the cut.
+  pub fn speed(&self) -> Time {
+    self.speed
+  }
+
+  /// Change the playback speed of the cut, scaling its duration accordingly.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `speed` isn’t strictly positive.
+  pub fn set_speed(&mut self, speed: Time) {
+    assert!(speed > 0.);
+    self.speed = speed;
+  }
+
+  /// Span of the clip used by the cut.
+  pub fn span(&self) -> Time {
+    self.out_time - self.in_time
+  }
+
+  /// Duration of the cut. The speed being strictly positive, it’s never negative.
+  pub fn dur(&self) -> Time {
+    self.span() / self.speed
+  }
+
+  /// Time of the timeline at which the cut ends.
+  pub fn end_time(&self) -> Time {
+    self.inst_time + self.dur()
+  }
+
+  /// Whether the cut plays at a given time of the timeline.
+  ///
+  /// A cut plays from its *instance time* included to its end excluded, so that a cut and the one
+  /// starting right at its end – the two halves of a split cut, for instance – never play together.
+  pub fn is_active(&self, t: Time) -> bool {
+    self.inst_time <= t && t < self.end_time()
+  }
+
+  /// Time in the clip at a given time of the timeline.
+  pub fn clip_time(&self, t: Time) -> Time {
+    let progress = self.progress(t);
+    let position = match self.remap {
+      Some(ref remap) => remap.clamped_sample(progress as f32).map_or(progress, |x| x as Time),
+      None => progress
+    };
+    let position = if self.reverse { 1. - position } else { position };
+
+    self.in_time + position * self.span()
+  }
+
+  /// Context in which the clip is played at a given time of the timeline.
+  pub fn context(&self, t: Time) -> ClipContext {
+    ClipContext {
+      global: t,
+      local: self.clip_time(t),
+      progress: self.progress(t),
+      dur: self.dur()
+    }
+  }
+
+  // Normalized progress in the cut.
+  fn progress(&self, t: Time) -> Time {
+    let dur = self.dur();
+
+    if dur > 0. { (t - self.inst_time) / dur } else { 0. }
+  }
+}
+
+/// A track gathers `Cut`s and its purpose is to be used inside a `Timeline`.
+///
+/// Cuts are kept sorted by *instance time*; editing operations refer to cuts by their index in
+/// that order.
+#[derive(Clone)]
+pub struct Track<'a, 'b, 'c> where 'a: 'b, 'b: 'c {
+  cuts: Vec<Cut<'a, 'b, 'c>>,
+  muted: bool,
+  solo: bool
+}
+
+impl<'a, 'b, 'c> Track<'a, 'b, 'c> where 'a: 'b, 'b: 'c {
+  pub fn new() -> Self {
+    Track {
+      cuts: Vec::new(),
+      muted: false,
+      solo: false
+    }
+  }
+
+  /// A muted track doesn’t play.
+  pub fn is_muted(&self) -> bool {
+    self.muted
+  }
+
+  pub fn set_muted(&mut self, muted: bool) {
+    self.muted = muted;
+  }
+
+  /// Mute or unmute the track and return whether it’s now muted.
+  pub fn toggle_mute(&mut self) -> bool {
+    self.muted = !self.muted;
+    self.muted
+  }
+
+  /// When at least one track of a timeline is soloed, only soloed tracks play.
+  pub fn is_solo(&self) -> bool {
+    self.solo
+  }
+
+  pub fn set_solo(&mut self, solo: bool) {
+    self.solo = solo;
+  }
+
+  /// Solo or unsolo the track and return whether it’s now soloed.
+  pub fn toggle_solo(&mut self) -> bool {
+    self.solo = !self.solo;
+    self.solo
+  }
+
+  pub fn cuts(&self) -> &[Cut<'a, 'b, 'c>] {
+    &self.cuts
+  }
+
+  /// Time span covered by the cuts of the track, if any.
+  pub fn bounds(&self) -> Option<(Time, Time)> {
+    self.cuts.first().map(|first| {
+      let end = self.cuts.iter().fold(first.end_time(), |end, cut| end.max(cut.end_time()));
+      (first.inst_time, end)
+    })
+  }
+
+  /// Add a cut and return its index.
+  pub fn add_cut(&mut self, cut: Cut<'a, 'b, 'c>) -> usize {
+    let i = self.cuts.iter().position(|c| c.inst_time > cut.inst_time).unwrap_or(self.cuts.len());
+    self.cuts.insert(i, cut);
+    i
+  }
+
+  pub fn remove_cut(&mut self, i: usize) -> Option<Cut<'a, 'b, 'c>> {
+    if i < self.cuts.len() {
+      Some(self.cuts.remove(i))
+    } else {
+      None
+    }
+  }
+
+  /// Split the cut playing at a given time in two cuts, the second one starting at that time.
+  ///
+  /// Return the index of the second cut, or `None` if no cut can be split at that time. Cuts with
+  /// a time remapping cannot be split.
+  pub fn split(&mut self, t: Time) -> Option<usize> {
+    let i = match self.cuts.iter().position(|c| c.remap.is_none() && c.inst_time < t && t < c.inst_time + c.dur()) {
+      Some(i) => i,
+      None => return None
+    };
+    let mut second = self.cuts[i].clone();
+    let split_time = second.clip_time(t);
+
+    if second.reverse {
+      self.cuts[i].in_time = split_time;
+      second.out_time = split_time;
+    } else {
+      self.cuts[i].out_time = split_time;
+      second.in_time = split_time;
+    }
+
+    second.inst_time = t;
+
+    Some(self.add_cut(second))
+  }
+
+  /// Change the *input time* of a cut, keeping its content in place: the cut starts earlier or
+  /// later accordingly – or ends earlier or later if the cut is reversed.
+  ///
+  /// Return the new index of the cut, or `None` if the cut doesn’t exist or would end before it
+  /// starts.
+  pub fn trim_in(&mut self, i: usize, in_time: Time) -> Option<usize> {
+    if i >= self.cuts.len() || in_time > self.cuts[i].out_time {
+      return None;
+    }
+
+    let mut cut = self.cuts.remove(i);
+
+    if !cut.reverse {
+      cut.inst_time += (in_time - cut.in_time) / cut.speed;
+    }
+
+    cut.in_time = in_time;
+
+    Some(self.add_cut(cut))
+  }
+
+  /// Change the *output time* of a cut, keeping its content in place: the cut ends earlier or later
+  /// accordingly – or starts earlier or later if the cut is reversed.
+  ///
+  /// Return the new index of the cut, or `None` if the cut doesn’t exist or would end before it
+  /// starts.
+  pub fn trim_out(&mut self, i: usize, out_time: Time) -> Option<usize> {
+    if i >= self.cuts.len() || out_time < self.cuts[i].in_time {
+      return None;
+    }
+
+    let mut cut = self.cuts.remove(i);
+
+    if cut.reverse {
+      cut.inst_time -= (out_time - cut.out_time) / cut.speed;
+    }
+
+    cut.out_time = out_time;
+
+    Some(self.add_cut(cut))
+  }
+
+  /// Shift the content of a cut by changing its *input* and *output times*, keeping its placement
+  /// and duration.
+  ///
+  /// Return `false` if the cut doesn’t exist.
+  pub fn slip(&mut self, i: usize, dt: Time) -> bool {
+    match self.cuts.get_mut(i) {
+      Some(cut) => {
+        cut.in_time += dt;
+        cut.out_time += dt;
+        true
+      },
+      None => false
+    }
+  }
+
+  /// Move the edit point between a cut and the next one, lengthening one and shortening the other.
+  ///
+  /// Return `false` if there’s no next cut or if one of the cuts would end before it starts.
+  pub fn roll(&mut self, i: usize, dt: Time) -> bool {
+    if i + 1 >= self.cuts.len() || self.cuts[i].dur() + dt < 0. || self.cuts[i+1].dur() - dt < 0. {
+      return false;
+    }
+
+    {
+      let cut = &mut self.cuts[i];
+
+      if cut.reverse {
+        cut.in_time -= dt * cut.speed;
+      } else {
+        cut.out_time += dt * cut.speed;
+      }
+    }
+
+    let next = &mut self.cuts[i+1];
+
+    if next.reverse {
+      next.out_time -= dt * next.speed;
+    } else {
+      next.in_time += dt * next.speed;
+    }
+
+    next.inst_time += dt;
+
+    true
+  }
+
+  /// Remove a cut and move all the cuts after it earlier to close the gap.
+  ///
+  /// The overlaps and markers of the timeline are left in place; see `Timeline::ripple_delete`.
+  pub fn ripple_delete(&mut self, i: usize) -> Option<Cut<'a, 'b, 'c>> {
+    self.remove_cut(i).map(|cut| {
+      let dur = cut.dur();
+
+      for next in &mut self.cuts[i..] {
+        next.inst_time -= dur;
+      }
+
+      cut
+    })
+  }
+}
+
+impl<'a, 'b, 'c, 'd> From<&'d [Cut<'a, 'b, 'c>]> for Track<'a, 'b, 'c> {
+  fn from(cuts: &'d [Cut<'a, 'b, 'c>]) -> Self {
+    let mut cuts = cuts.to_vec();
+    cuts.sort_by(|a, b| a.inst_time.partial_cmp(&b.inst_time).unwrap());
+
+    Track {
+      cuts: cuts,
+      muted: false,
+      solo: false
+    }
+  }
+}
+
+/// A timeline gathers tracks used to build up the visual aspect of the demo.
+pub struct Timeline<'a, 'b, 'c> where 'a: 'b, 'b: 'c {
+  tracks: Vec<Track<'a, 'b, 'c>>,
+  overlaps: Vec<Overlap<'a>>,
+  // sorted by time
+  markers: Vec<Marker>,
+  // name and tempo map of the manifest the timeline was built from, used to write musical times back
+  tempo: Option<(String, TempoMap)>,
+  // sub-timelines of the manifest the timeline was built from
+  timelines: Vec<String>,
+  sub_manifests: HashMap<String, TimelineManifest>
+}
+
+impl<'a, 'b, 'c> Timeline<'a, 'b, 'c> where 'a: 'b, 'b: 'c {
+  pub fn new() -> Self {
+    Timeline {
+      tracks: Vec::new(),
+      overlaps: Vec::new(),
+      markers: Vec::new(),
+      tempo: None,
+      timelines: Vec::new(),
+      sub_manifests: HashMap::new()
+    }
+  }
+
+  /// Turn a TimelineManifest into a Timeline by providing a mapping between clips’ names and real
+  /// clips, and a registry of transitions used by the overlaps.
+  ///
+  /// Cuts referencing sub-timelines are ignored; see `Timeline::from_nested_manifest`.
+  pub fn from_manifest(manifest: &TimelineManifest, mapping: &HashMap<String, &'c Clip<'a, 'b>>, transitions: &TransitionRegistry<'a>) -> Self {
+    Self::build(manifest, mapping, transitions, None)
+  }
+
+  /// Turn a TimelineManifest into a Timeline, playing the sub-timelines it references with clips
+  /// built by `SubClips::new`.
+  pub fn from_nested_manifest(manifest: &TimelineManifest, mapping: &HashMap<String, &'c Clip<'a, 'b>>, transitions: &TransitionRegistry<'a>, sub_clips: &'c SubClips<'a, 'b>) -> Self {
+    Self::build(manifest, mapping, transitions, Some(sub_clips))
+  }
+
+  fn build(manifest: &TimelineManifest, mapping: &HashMap<String, &'c Clip<'a, 'b>>, transitions: &TransitionRegistry<'a>, sub_clips: Option<&'c SubClips<'a, 'b>>) -> Self {
+    let mut timeline = Self::new();
+    // times in seconds; units are taken from the manifest as written
+    let resolved = manifest.resolved();
+
+    timeline.tempo = match (manifest.tempo.clone(), manifest.tempo_map.clone()) {
+      (Some(name), Some(tempo)) => Some((name, tempo)),
+      _ => None
+    };
+    timeline.timelines = manifest.timelines.clone();
+    timeline.sub_manifests = manifest.sub_manifests.clone();
+
+    for (track_manifest, written_track) in resolved.tracks.iter().zip(&manifest.tracks) {
+      let mut track = Track::new();
+      track.set_muted(track_manifest.muted);
+      track.set_solo(track_manifest.solo);
+
+      for (cut_manifest, written) in track_manifest.cuts.iter().zip(&written_track.cuts) {
+        let times = (cut_manifest.in_time.secs(), cut_manifest.out_time.secs(), cut_manifest.inst_time.secs());
+        let (in_time, out_time, inst_time) = match times {
+          (Some(in_time), Some(out_time), Some(inst_time)) => (in_time, out_time, inst_time),
+          _ => {
+            warn!("the cut of {:?} is in musical time but the timeline has no tempo map", cut_manifest.clip);
+            continue;
+          }
+        };
+
+        if in_time > out_time {
+          warn!("the cut of {:?} ends before it starts", cut_manifest.clip);
+          continue;
+        }
+
+        if cut_manifest.speed <= 0. {
+          warn!("the cut of {:?} has a non-positive speed", cut_manifest.clip);
+          continue;
+        }
+
+        let clip = if manifest.sub_manifests.contains_key(&cut_manifest.clip) {
+          sub_clips.and_then(|sub_clips| sub_clips.clips.get(&cut_manifest.clip))
+        } else {
+          mapping.get(&cut_manifest.clip).cloned()
+        };
+
+        if let Some(clip) = clip {
+          let mut cut = Cut::new(in_time, out_time, inst_time, clip);
+          cut.name = Some(cut_manifest.clip.clone());
+          cut.set_speed(cut_manifest.speed);
+          cut.reverse = cut_manifest.reverse;
+          cut.remap = cut_manifest.remap.clone();
+          cut.in_unit = written.in_time.unit();
+          cut.out_unit = written.out_time.unit();
+          cut.inst_unit = written.inst_time.unit();
+
+          track.add_cut(cut);
+        } else {
+          warn!("the clip {:?} doesn’t exist", cut_manifest.clip);
+        }
+      }
+
+      timeline.add_track(track);
+    }
+
+    for (overlap_manifest, written) in resolved.overlaps.iter().zip(&manifest.overlaps) {
+      let (inst_time, dur) = match (overlap_manifest.inst_time.secs(), overlap_manifest.dur.secs()) {
+        (Some(inst_time), Some(dur)) => (inst_time, dur),
+        _ => {
+          warn!("the overlap {:?} is in musical time but the timeline has no tempo map", overlap_manifest.transition);
+          continue;
+        }
+      };
+
+      if let Some(mut overlap) = transitions.overlap(&overlap_manifest.transition, inst_time, dur) {
+        overlap.inst_unit = written.inst_time.unit();
+        overlap.dur_unit = written.dur.unit();
+
+        timeline.add_overlap(overlap);
+      } else {
+        warn!("the transition {:?} doesn’t exist", overlap_manifest.transition);
+      }
+    }
+
+    for (marker_manifest, written) in resolved.markers.iter().zip(&manifest.markers) {
+      if let Some(time) = marker_manifest.time.secs() {
+        let mut marker = Marker::new(time, marker_manifest.label.clone(), marker_manifest.payload.clone());
+        marker.unit = written.time.unit();
+
+        timeline.add_marker(marker);
+      } else {
+        warn!("the marker {:?} is in musical time but the timeline has no tempo map", marker_manifest.label);
+      }
+    }
+
+    timeline
+  }
+
+  /// Turn the Timeline back into a TimelineManifest, naming clips with the same kind of mapping
+  /// as the one given to `Timeline::from_manifest` when cuts don’t have a name.
+  ///
+  /// Unnamed cuts which clip isn’t in the mapping and overlaps not built out of a
+  /// `TransitionRegistry` are left out.
+  ///
+  /// The tempo map and the sub-timelines of the manifest the timeline was built from are kept, and
+  /// times are written back in the units they were written in.
+  pub fn to_manifest(&self, mapping: &HashMap<String, &'c Clip<'a, 'b>>) -> TimelineManifest {
+    let tracks = self.tracks.iter().map(|track| {
+      let cuts = track.cuts.iter().filter_map(|cut| {
+        let name = cut.name.clone().or_else(|| {
+          mapping.iter().find(|&(_, clip)| *clip as *const Clip == cut.clip as *const Clip).map(|(name, _)| name.clone())
+        });
+
+        if name.is_none() {
+          warn!("a cut at {} uses a clip missing from the mapping", cut.inst_time);
+        }
+
+        name.map(|name| {
+          // like TimelineManifest::resolve_times, positions in the clip are measured from the
+          // instance time, and an output time in the same musical unit as the input time is
+          // measured from it
+          let in_time = self.dur_spec(cut.inst_time, cut.in_time, cut.in_unit);
+          let out_time = match (in_time, self.dur_spec(cut.inst_time, cut.span(), cut.out_unit)) {
+            (TimeSpec::Beats { beats: a }, TimeSpec::Beats { beats: span }) => TimeSpec::Beats { beats: a + span },
+            (TimeSpec::Bars { bars: a }, TimeSpec::Bars { bars: span }) => TimeSpec::Bars { bars: a + span },
+            _ => self.dur_spec(cut.inst_time, cut.out_time, cut.out_unit)
+          };
+
+          CutManifest {
+            in_time: in_time,
+            out_time: out_time,
+            inst_time: self.time_spec(cut.inst_time, cut.inst_unit),
+            clip: name,
+            speed: cut.speed,
+            reverse: cut.reverse,
+            remap: cut.remap.clone()
+          }
+        })
+      }).collect();
+
+      TrackManifest {
+        cuts: cuts,
+        muted: track.muted,
+        solo: track.solo
+      }
+    }).collect();
+
+    let overlaps = self.overlaps.iter().filter_map(|overlap| {
+      overlap.transition.as_ref().map(|transition| OverlapManifest {
+        inst_time: self.time_spec(overlap.inst_time, overlap.inst_unit),
+        dur: self.dur_spec(overlap.inst_time, overlap.dur, overlap.dur_unit),
+        transition: transition.clone()
+      })
+    }).collect();
+
+    let markers = self.markers.iter().map(|marker| MarkerManifest {
+      time: self.time_spec(marker.time, marker.unit),
+      label: marker.label.clone(),
+      payload: marker.payload.clone()
+    }).collect();
+
+    TimelineManifest {
+      tempo: self.tempo.as_ref().map(|&(ref name, _)| name.clone()),
+      timelines: self.timelines.clone(),
+      tracks: tracks,
+      overlaps: overlaps,
+      markers: markers,
+      tempo_map: self.tempo.as_ref().map(|&(_, ref tempo)| tempo.clone()),
+      sub_manifests: self.sub_manifests.clone()
+    }
+  }
+
+  /// Name of the tempo map of the manifest the timeline was built from, if any.
+  pub fn tempo(&self) -> Option<&str> {
+    self.tempo.as_ref().map(|&(ref name, _)| name.as_str())
+  }
+
+  // Express a time in a unit, in seconds if the timeline has no tempo map.
+  fn time_spec(&self, t: Time, unit: TimeUnit) -> TimeSpec {
+    match self.tempo {
+      Some((_, ref tempo)) => unit.to_spec(t, tempo),
+      None => TimeSpec::Secs(t)
+    }
+  }
+
+  // Express a duration starting at a given time in a unit, in seconds if the timeline has no tempo
+  // map.
+  fn dur_spec(&self, start: Time, dur: Time, unit: TimeUnit) -> TimeSpec {
+    match self.tempo {
+      Some((_, ref tempo)) => unit.dur_to_spec(start, dur, tempo),
+      None => TimeSpec::Secs(dur)
+    }
+  }
+
+  pub fn tracks(&self) -> &[Track<'a, 'b, 'c>] {
+    &self.tracks
+  }
+
+  /// Cuts playing at a given time, along with the indices of their tracks. Muted tracks are
+  /// included.
+  ///
+  /// At an edit point, only the cut starting there is reported – see `Cut::is_active`.
+  pub fn active_cuts(&self, t: Time) -> Vec<(usize, &Cut<'a, 'b, 'c>)> {
+    self.tracks.iter().enumerate().flat_map(|(track_index, track)| {
+      track.cuts.iter().filter(move |cut| cut.is_active(t)).map(move |cut| (track_index, cut))
+    }).collect()
+  }
+
+  /// First edit point – start or end of a cut – strictly after a given time.
+  pub fn next_edit_point(&self, t: Time) -> Option<Time> {
+    self.edit_points().filter(|&x| x > t).fold(None, |next, x| Some(next.map_or(x, |next: Time| next.min(x))))
+  }
+
+  /// Last edit point – start or end of a cut – strictly before a given time.
+  pub fn previous_edit_point(&self, t: Time) -> Option<Time> {
+    self.edit_points().filter(|&x| x < t).fold(None, |prev, x| Some(prev.map_or(x, |prev: Time| prev.max(x))))
+  }
+
+  /// Duration of the timeline: time at which its last cut ends.
+  pub fn duration(&self) -> Time {
+    self.tracks.iter().filter_map(|track| track.bounds()).fold(0., |dur: Time, (_, end)| dur.max(end))
+  }
+
+  // Starts and ends of all cuts.
+  fn edit_points<'t>(&'t self) -> Box<Iterator<Item = Time> + 't> {
+    Box::new(self.tracks.iter().flat_map(|track| track.cuts.iter()).flat_map(|cut| once(cut.inst_time).chain(once(cut.end_time()))))
+  }
+
+  pub fn track_mut(&mut self, i: usize) -> Option<&mut Track<'a, 'b, 'c>> {
+    self.tracks.get_mut(i)
+  }
+
+  /// Mute or unmute a track and return whether it’s now muted, or `None` if it doesn’t exist.
+  pub fn toggle_mute(&mut self, i: usize) -> Option<bool> {
+    self.tracks.get_mut(i).map(|track| track.toggle_mute())
+  }
+
+  /// Solo or unsolo a track and return whether it’s now soloed, or `None` if it doesn’t exist.
+  pub fn toggle_solo(&mut self, i: usize) -> Option<bool> {
+    self.tracks.get_mut(i).map(|track| track.toggle_solo())
+  }
+
+  pub fn add_track(&mut self, track: Track<'a, 'b, 'c>) {
+    self.tracks.push(track);
+  }
+
+  pub fn remove_track(&mut self, i: usize) -> Option<Track<'a, 'b, 'c>> {
+    if i < self.tracks.len() {
+      Some(self.tracks.remove(i))
+    } else {
+      None
+    }
+  }
+
+  /// Move a cut from a track to another one, keeping its placement.
+  ///
+  /// Return the index of the cut in its new track, or `None` if either track or the cut doesn’t
+  /// exist.
+  pub fn move_cut(&mut self, from_track: usize, i: usize, to_track: usize) -> Option<usize> {
+    if to_track >= self.tracks.len() {
+      return None;
+    }
+
+    let cut = match self.tracks.get_mut(from_track).and_then(|track| track.remove_cut(i)) {
+      Some(cut) => cut,
+      None => return None
+    };
+
+    Some(self.tracks[to_track].add_cut(cut))
+  }
+
+  /// Remove a cut and move everything after it earlier to close the gap: the next cuts of its
+  /// track, and the overlaps and markers starting at or after its end.
+  ///
+  /// Return the removed cut, or `None` if the track or the cut doesn’t exist.
+  pub fn ripple_delete(&mut self, track: usize, i: usize) -> Option<Cut<'a, 'b, 'c>> {
+    let cut = match self.tracks.get_mut(track).and_then(|track| track.ripple_delete(i)) {
+      Some(cut) => cut,
+      None => return None
+    };
+    let (end, dur) = (cut.end_time(), cut.dur());
+
+    for overlap in self.overlaps.iter_mut().filter(|overlap| overlap.inst_time >= end) {
+      overlap.inst_time -= dur;
+    }
+
+    for marker in self.markers.iter_mut().filter(|marker| marker.time >= end) {
+      marker.time -= dur;
+    }
+
+    // markers inside the removed cut stay in place and may now come after the moved ones
+    self.markers.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+
+    Some(cut)
+  }
+
+  pub fn overlaps(&self) -> &[Overlap<'a>] {
+    &self.overlaps
+  }
+
+  pub fn add_overlap(&mut self, overlap: Overlap<'a>) {
+    self.overlaps.push(overlap)
+  }
+
+  pub fn remove_overlap(&mut self, i: usize) -> Option<Overlap<'a>> {
+    if i < self.overlaps.len() {
+      Some(self.overlaps.remove(i))
+    } else {
+      None
+    }
+  }
+
+  /// Markers, sorted by time.
+  pub fn markers(&self) -> &[Marker] {
+    &self.markers
+  }
+
+  /// Add a marker and return its index.
+  pub fn add_marker(&mut self, marker: Marker) -> usize {
+    let i = self.markers.iter().position(|m| m.time > marker.time).unwrap_or(self.markers.len());
+    self.markers.insert(i, marker);
+    i
+  }
+
+  pub fn remove_marker(&mut self, i: usize) -> Option<Marker> {
+    if i < self.markers.len() {
+      Some(self.markers.remove(i))
+    } else {
+      None
+    }
+  }
+
+  /// Markers crossed when playing from `from` to `to`, in `[from; to)`.
+  ///
+  /// Playing backwards doesn’t cross any marker.
+  pub fn crossed_markers(&self, from: Time, to: Time) -> &[Marker] {
+    if to <= from {
+      return &self.markers[0..0];
+    }
+
+    let start = self.markers.iter().position(|m| m.time >= from).unwrap_or(self.markers.len());
+    let end = self.markers.iter().position(|m| m.time >= to).unwrap_or(self.markers.len());
+
+    &self.markers[start..end]
+  }
+
+  pub fn play(&self, t: Time) -> Played<'a> {
+    let mut active_nodes = Vec::new();
+
+    // populate the active nodes
+    let solo = self.tracks.iter().any(|track| track.solo);
+
+    for track in self.tracks.iter().filter(|track| !track.muted && (!solo || track.solo)) {
+      for cut in &track.cuts {
+        if cut.is_active(t) {
+          active_nodes.push((cut.clip.gen_node)(cut.context(t)));
+        }
+      }
+    }
+
+    // apply overlap if needed
+    match active_nodes.len() {
+      0 => Played::Inactive,
+      1 => active_nodes.pop().map(Played::Resolved).unwrap_or(Played::Inactive),
+      _ => {
+        // we need to seek for an overlap here because we have strictly more than one node in hands
+        self.find_overlap(t).map(|overlap| {
+          Played::Resolved((overlap.fold)(active_nodes, overlap.progress(t)))
+        }).unwrap_or(Played::NoOverlap)
+      }
+    }
+  }
+
+  /// Find an active overlap at the given time.
+  fn find_overlap(&self, t: Time) -> Option<&Overlap<'a>> {
+    self.overlaps.iter().find(|x| x.is_active(t))
+  }
+}
+
+/// Clips playing the sub-timelines referenced by a `TimelineManifest`.
+///
+/// Only one level of nesting is supported: sub-timelines cannot reference other timelines.
+pub struct SubClips<'a, 'b> where 'a: 'b {
+  clips: HashMap<String, Clip<'a, 'b>>
+}
+
+impl<'a, 'b> SubClips<'a, 'b> where 'a: 'b {
+  /// Build the sub-timelines of a manifest, using a mapping between clips’ names and real clips and
+  /// a registry of transitions.
+  ///
+  /// Fail if a sub-timeline references other timelines.
+  pub fn new<'x>(manifest: &TimelineManifest, mapping: &HashMap<String, &'b Clip<'a, 'x>>, transitions: &TransitionRegistry<'a>) -> Result<Self> where 'a: 'x, 'x: 'b {
+    let mut clips = HashMap::new();
+
+    for (name, sub_manifest) in &manifest.sub_manifests {
+      if !sub_manifest.timelines.is_empty() {
+        return Err(LoadError::ConversionFailed(format!("the timeline {:?} references other timelines, which is not supported in sub-timelines", name)));
+      }
+
+      clips.insert(name.clone(), Clip::from_timeline(Timeline::from_manifest(sub_manifest, mapping, transitions)));
+    }
+
+    Ok(SubClips {
+      clips: clips
+    })
+  }
+}
+
+/// Named point in time of a timeline, used to trigger one-shot events.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+  pub time: Time,
+  pub label: String,
+  /// User data attached to the marker.
+  pub payload: Option<Value>,
+  /// Unit the time was written in; see `Cut`.
+  pub unit: TimeUnit
+}
+
+impl Marker {
+  pub fn new<L>(time: Time, label: L, payload: Option<Value>) -> Self where L: Into<String> {
+    Marker {
+      time: time,
+      label: label.into(),
+      payload: payload,
+      unit: TimeUnit::Secs
+    }
+  }
+}
+
+/// Cursor reporting the markers crossed while a timeline plays.
+///
+/// Advancing the cursor reports the markers between its previous position and the new one. Moving
+/// backwards – scrubbing in an editor, for instance – or seeking doesn’t report anything.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MarkerCursor {
+  t: Time
+}
+
+impl MarkerCursor {
+  /// Create a cursor at a given time.
+  pub fn new(t: Time) -> Self {
+    MarkerCursor {
+      t: t
+    }
+  }
+
+  /// Current position of the cursor.
+  pub fn time(&self) -> Time {
+    self.t
+  }
+
+  /// Move the cursor to a given time and return the crossed markers.
+  pub fn advance<'t, 'a, 'b, 'c>(&mut self, timeline: &'t Timeline<'a, 'b, 'c>, t: Time) -> &'t [Marker] where 'a: 'b, 'b: 'c {
+    let from = self.t;
+    self.t = t;
+
+    timeline.crossed_markers(from, t)
+  }
+
+  /// Move the cursor to a given time without reporting any marker.
+  pub fn seek(&mut self, t: Time) {
+    self.t = t;
+  }
+}
+
+/// Informational value giving hints about how a timeline has played.
+pub enum Played<'a> {
+  /// The timeline has correctly resolved everything and a `Node` is available
+  Resolved(Node<'a>),
+  /// There are active `Node`s but no overlap to fold them.
+  NoOverlap,
+  /// No active `Node`s.
+  Inactive
+}
+
+/// Timeline description.
+///
+/// If `tempo` names a tempo map, the times of the cuts can be expressed in beats or bars. The tempo
+/// map is loaded along with the manifest into `tempo_map` and times are kept as written; they get
+/// converted into seconds when a `Timeline` is built or the manifest is linted – see
+/// `TimelineManifest::resolved`.
+///
+/// `timelines` names other timeline manifests that cuts can use as clips. They’re loaded along with
+/// the manifest into `sub_manifests`. Only one level of nesting is supported: a manifest which
+/// sub-timelines reference other timelines – or itself – fails to load.
+///
+/// `overlaps` gives the transitions – by name – to apply where cuts overlap and `markers` the cue
+/// points of the timeline.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TimelineManifest {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub tempo: Option<String>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub timelines: Vec<String>,
+  pub tracks: Vec<TrackManifest>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub overlaps: Vec<OverlapManifest>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub markers: Vec<MarkerManifest>,
+  #[serde(skip_serializing, skip_deserializing)]
+  pub tempo_map: Option<TempoMap>,
+  #[serde(skip_serializing, skip_deserializing)]
+  pub sub_manifests: HashMap<String, TimelineManifest>
+}
+
+impl TimelineManifest {
+  /// Copy of the manifest with all times in seconds, converted with `tempo_map`. Without a tempo
+  /// map, the copy is left as is.
+  pub fn resolved(&self) -> Self {
+    let mut manifest = self.clone();
+
+    if let Some(ref tempo) = self.tempo_map {
+      manifest.resolve_times(tempo);
+    }
+
+    manifest
+  }
+
+  /// Convert all times expressed in musical time into seconds.
+  ///
+  /// The *input* and *output times* of cuts are positions in their clips: they’re measured from
+  /// the *instance time* of the cut, at the tempo the cut plays at.
+  pub fn resolve_times(&mut self, tempo: &TempoMap) {
+    for track in &mut self.tracks {
+      for cut in &mut track.cuts {
+        let inst_time = cut.inst_time.to_secs(tempo);
+        let in_time = cut.in_time.dur_to_secs(inst_time, tempo);
+        let span = match (cut.in_time, cut.out_time) {
+          (TimeSpec::Beats { beats: a }, TimeSpec::Beats { beats: b }) => TimeSpec::Beats { beats: b - a }.dur_to_secs(inst_time, tempo),
+          (TimeSpec::Bars { bars: a }, TimeSpec::Bars { bars: b }) => TimeSpec::Bars { bars: b - a }.dur_to_secs(inst_time, tempo),
+          (_, out_time) => out_time.dur_to_secs(inst_time, tempo) - in_time
+        };
+
+        cut.in_time = TimeSpec::Secs(in_time);
+        cut.out_time = TimeSpec::Secs(in_time + span);
+        cut.inst_time = TimeSpec::Secs(inst_time);
+      }
+    }
+
+    for overlap in &mut self.overlaps {
+      let inst_time = overlap.inst_time.to_secs(tempo);
+
+      overlap.dur = TimeSpec::Secs(overlap.dur.dur_to_secs(inst_time, tempo));
+      overlap.inst_time = TimeSpec::Secs(inst_time);
+    }
+
+    for marker in &mut self.markers {
+      marker.time.resolve(tempo);
+    }
+  }
+
+  /// Check the structure of the timeline: invalid cuts, cuts overlapping on the same track, times
+  /// where several cuts are active without any overlap to fold them and gaps.
+  ///
+  /// Uncovered overlaps and gaps are found the way `Timeline::play` works: cuts and overlaps are
+  /// active from their start included to their end excluded, and only the tracks that play – not
+  /// muted, and soloed if any track is – are considered.
+  ///
+  /// Times are resolved with `TimelineManifest::resolved`. Cuts and overlaps in musical time that
+  /// couldn’t be resolved are reported and otherwise ignored.
+  pub fn lint(&self) -> Vec<Lint> {
+    let manifest = self.resolved();
+    let mut lints = Vec::new();
+    // (track, cut, start, end) of every valid cut
+    let mut cuts = Vec::new();
+    let solo = manifest.tracks.iter().any(|track| track.solo);
+
+    for (track_index, track) in manifest.tracks.iter().enumerate() {
+      let mut track_cuts: Vec<(usize, Time, Time)> = Vec::new();
+
+      for (cut_index, cut) in track.cuts.iter().enumerate() {
+        let (in_time, out_time, inst_time) = match (cut.in_time.secs(), cut.out_time.secs(), cut.inst_time.secs()) {
+          (Some(in_time), Some(out_time), Some(inst_time)) => (in_time, out_time, inst_time),
+          _ => {
+            lints.push(Lint::UnresolvedCutTime { track: track_index, cut: cut_index });
+            continue;
+          }
+        };
+
+        if in_time > out_time {
+          lints.push(Lint::InvalidCut { track: track_index, cut: cut_index, in_time: in_time, out_time: out_time });
+          continue;
+        }
+
+        if cut.speed <= 0. {
+          lints.push(Lint::InvalidSpeed { track: track_index, cut: cut_index, speed: cut.speed });
+          continue;
+        }
+
+        track_cuts.push((cut_index, inst_time, inst_time + (out_time - in_time) / cut.speed));
+      }
+
+      track_cuts.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+      for (i, &(cut_a, _, end_a)) in track_cuts.iter().enumerate() {
+        // cuts without duration never play
+        for &(cut_b, start_b, end_b) in track_cuts[i+1..].iter().take_while(|c| c.1 < end_a).filter(|c| c.1 < c.2) {
+          lints.push(Lint::OverlappingCuts { track: track_index, cuts: (cut_a, cut_b), start: start_b, end: end_a.min(end_b) });
+        }
+      }
+
+      cuts.extend(track_cuts.into_iter().map(|(cut, start, end)| (track_index, cut, start, end)));
+    }
+
+    let mut overlaps = Vec::new();
+
+    for (overlap_index, overlap) in manifest.overlaps.iter().enumerate() {
+      match (overlap.inst_time.secs(), overlap.dur.secs()) {
+        (Some(inst_time), Some(dur)) => overlaps.push((inst_time, inst_time + dur)),
+        _ => lints.push(Lint::UnresolvedOverlapTime { overlap: overlap_index })
+      }
+    }
+
+    for (marker_index, marker) in manifest.markers.iter().enumerate() {
+      if marker.time.secs().is_none() {
+        lints.push(Lint::UnresolvedMarkerTime { marker: marker_index });
+      }
+    }
+
+    let timeline_end = cuts.iter().fold(0., |end: Time, c| end.max(c.3));
+    let playing: Vec<_> = cuts.into_iter().filter(|c| {
+      let track = &manifest.tracks[c.0];
+      !track.muted && (!solo || track.solo)
+    }).collect();
+
+    // split the timeline at every boundary; what is active doesn’t change within a span, so it’s
+    // looked at the start of each span
+    let mut bounds = vec![0.];
+    bounds.extend(playing.iter().flat_map(|c| vec![c.2, c.3]));
+    bounds.extend(overlaps.iter().flat_map(|o| vec![o.0, o.1]));
+    bounds.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    bounds.dedup();
+
+    for span in bounds.windows(2) {
+      let (start, end) = (span[0], span[1]);
+
+      if end > timeline_end {
+        break;
+      }
+
+      let t = start;
+      let active: Vec<_> = playing.iter().filter(|c| c.2 <= t && t < c.3).collect();
+
+      if active.is_empty() {
+        push_span(&mut lints, Lint::Gap { start: start, end: end });
+      } else if active.len() > 1 && !overlaps.iter().any(|o| o.0 <= t && t < o.1) {
+        let mut tracks: Vec<usize> = active.iter().map(|c| c.0).collect();
+        tracks.dedup();
+
+        push_span(&mut lints, Lint::UncoveredOverlap { tracks: tracks, start: start, end: end });
+      }
+    }
+
+    lints
+  }
+
+  /// Check the names used by the timeline: clips missing from a mapping between clips’ names and
+  /// clips – sub-timelines aside – and transitions missing from a registry.
+  pub fn lint_names<C>(&self, mapping: &HashMap<String, C>, transitions: &TransitionRegistry) -> Vec<Lint> {
+    let mut lints = Vec::new();
+
+    for (track_index, track) in self.tracks.iter().enumerate() {
+      for (cut_index, cut) in track.cuts.iter().enumerate() {
+        if !mapping.contains_key(&cut.clip) && !self.sub_manifests.contains_key(&cut.clip) {
+          lints.push(Lint::MissingClip { track: track_index, cut: cut_index, clip: cut.clip.clone() });
+        }
+      }
+    }
+
+    for (overlap_index, overlap) in self.overlaps.iter().enumerate() {
+      if transitions.get(&overlap.transition).is_none() {
+        lints.push(Lint::UnknownTransition { overlap: overlap_index, transition: overlap.transition.clone() });
+      }
+    }
+
+    lints
+  }
+}
+
+// Push a lint covering a span of time, merging it with the last lint if it continues it.
+fn push_span(lints: &mut Vec<Lint>, lint: Lint) {
+  let merged = match (lints.last_mut(), &lint) {
+    (Some(&mut Lint::Gap { ref mut end, .. }), &Lint::Gap { start, end: new_end }) => {
+      if *end == start {
+        *end = new_end;
+        true
+      } else {
+        false
+      }
+    },
+    (Some(&mut Lint::UncoveredOverlap { ref tracks, ref mut end, .. }), &Lint::UncoveredOverlap { tracks: ref new_tracks, start, end: new_end }) => {
+      if *end == start && tracks == new_tracks {
+        *end = new_end;
+        true
+      } else {
+        false
+      }
+    },
+    _ => false
+  };
+
+  if !merged {
+    lints.push(lint);
+  }
+}
+
+/// Problem found in a `TimelineManifest`.
+///
+/// Tracks, cuts and overlaps are given by their indices in the manifest.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Lint {
+  /// The input time of a cut is after its output time.
+  InvalidCut { track: usize, cut: usize, in_time: Time, out_time: Time },
+  /// The speed of a cut isn’t strictly positive.
+  InvalidSpeed { track: usize, cut: usize, speed: Time },
+  /// A cut is in musical time but the timeline has no tempo map.
+  UnresolvedCutTime { track: usize, cut: usize },
+  /// An overlap is in musical time but the timeline has no tempo map.
+  UnresolvedOverlapTime { overlap: usize },
+  /// A marker is in musical time but the timeline has no tempo map.
+  UnresolvedMarkerTime { marker: usize },
+  /// Two cuts of the same track are active at the same time.
+  OverlappingCuts { track: usize, cuts: (usize, usize), start: Time, end: Time },
+  /// Cuts of several tracks are active at the same time but no overlap folds them.
+  UncoveredOverlap { tracks: Vec<usize>, start: Time, end: Time },
+  /// No cut is active.
+  Gap { start: Time, end: Time },
+  /// A cut uses a clip that doesn’t exist.
+  MissingClip { track: usize, cut: usize, clip: String },
+  /// An overlap uses a transition that doesn’t exist.
+  UnknownTransition { overlap: usize, transition: String }
+}
+
+impl Load for TimelineManifest {
+  type Args = ();
+
+  const TY_STR: &'static str = "edit";
+
+  fn load<P>(path: P, cache: &mut ResCache, _: Self::Args) -> Result<Self> where P: AsRef<Path> {
+    let path = path.as_ref();
+
+    info!("loading timeline: {:?}", path);
+
+    let file = File::open(path).map_err(|e| LoadError::FileNotFound(path.to_path_buf(), format!("{:?}", e)))?;
+    let mut manifest: TimelineManifest = from_reader(file).map_err(|e| LoadError::ParseFailed(format!("{:?}", e)))?;
+
+    if let Some(tempo_key) = manifest.tempo.clone() {
+      let tempo = cache.get::<TempoMap>(&tempo_key, ()).ok_or_else(|| LoadError::ConversionFailed(format!("unknown tempo map {:?}", tempo_key)))?;
+      manifest.tempo_map = Some(tempo.borrow().clone());
+    }
+
+    for name in manifest.timelines.clone() {
+      if cache.is_loading::<TimelineManifest>(&name) {
+        return Err(LoadError::ConversionFailed(format!("the timeline {:?} references itself", name)));
+      }
+
+      let sub_manifest = cache.get::<TimelineManifest>(&name, ()).ok_or_else(|| LoadError::ConversionFailed(format!("unable to load the timeline {:?}", name)))?;
+
+      if !sub_manifest.borrow().timelines.is_empty() {
+        return Err(LoadError::ConversionFailed(format!("the timeline {:?} references other timelines, which is not supported in sub-timelines", name)));
+      }
+
+      manifest.sub_manifests.insert(name, sub_manifest.borrow().clone());
+    }
+
+    for lint in manifest.lint() {
+      warn!("{:?}: {:?}", path, lint);
+    }
+
+    Ok(manifest)
+  }
+}
+
+impl Save for TimelineManifest {
+  fn save<P>(&self, path: P) -> ::std::result::Result<(), SaveError> where P: AsRef<Path> {
+    let path = path.as_ref();
+
+    info!("saving timeline: {:?}", path);
+
+    let mut file = File::create(path).map_err(|e| SaveError::FileCreationFailed(path.to_path_buf(), format!("{:?}", e)))?;
+    to_writer_pretty(&mut file, self).map_err(|e| SaveError::SerializationFailed(format!("{:?}", e)))
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TrackManifest {
+  pub cuts: Vec<CutManifest>,
+  #[serde(default, skip_serializing_if = "is_false")]
+  pub muted: bool,
+  #[serde(default, skip_serializing_if = "is_false")]
+  pub solo: bool
+}
+
+fn is_false(b: &bool) -> bool {
+  !*b
+}
+
+/// Cut description.
+///
+/// `speed` must be strictly positive: other cuts are skipped when building a `Timeline` and
+/// reported by `TimelineManifest::lint`. `remap` is written like a spline file – a list of keys or
+/// a manifest with extrapolation modes. Like in `Cut`, its key times are the normalized progress
+/// in the cut, from `0` to `1`, so they cannot be expressed in beats or bars.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CutManifest {
+  pub in_time: TimeSpec,
+  pub out_time: TimeSpec,
+  pub inst_time: TimeSpec,
+  pub clip: String,
+  #[serde(default = "def_speed", skip_serializing_if = "is_def_speed")]
+  pub speed: Time,
+  #[serde(default, skip_serializing_if = "is_false")]
+  pub reverse: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "de_remap", serialize_with = "ser_remap")]
+  pub remap: Option<Spline<f32>>
+}
+
+// Time remappings are stored in the same format as spline files.
+fn de_remap<D>(deserializer: D) -> ::std::result::Result<Option<Spline<f32>>, D::Error> where D: Deserializer {
+  deserialize_spline(deserializer).map(Some)
+}
+
+fn ser_remap<S>(remap: &Option<Spline<f32>>, serializer: S) -> ::std::result::Result<S::Ok, S::Error> where S: Serializer {
+  match *remap {
+    Some(ref remap) => serialize_spline(remap, serializer),
+    None => serializer.serialize_none()
+  }
+}
+
+fn def_speed() -> Time {
+  1.
+}
+
+fn is_def_speed(speed: &Time) -> bool {
+  *speed == 1.
+}
+
+/// Overlap description: a named transition applied from `inst_time` for `dur`.
+///
+/// `dur` can be expressed in musical time as well, in which case it counts beats or bars from
+/// `inst_time`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OverlapManifest {
+  pub inst_time: TimeSpec,
+  pub dur: TimeSpec,
+  pub transition: String
+}
+
+/// Marker description. Its time can be expressed in musical time.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MarkerManifest {
+  pub time: TimeSpec,
+  pub label: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub payload: Option<Value>
+}
+
+/// An overlap is a fold of `Node`s down to a single `Node`. It’s used whenever two cuts overlap and
+/// need to be merged into a single one. It can be used for styling effect or transitions.
+///
+/// The fold is given the active nodes – in track order – and the normalized progress in the
+/// overlap.
+pub struct Overlap<'a> {
+  pub inst_time: Time,
+  pub dur: Time,
+  pub fold: Box<Fn(Vec<Node>, Time) -> Node + 'a>,
+  /// Name of the transition the overlap was built from, if built with a `TransitionRegistry`.
+  pub transition: Option<String>,
+  /// Units the times were written in; see `Cut`.
+  pub inst_unit: TimeUnit,
+  pub dur_unit: TimeUnit
+}
+
+impl<'a> Overlap<'a> {
+  pub fn new<F>(inst_time: Time, dur: Time, f: F) -> Self where F: 'a + Fn(Vec<Node>, Time) -> Node {
+    Overlap {
+      inst_time: inst_time,
+      dur: dur,
+      fold: Box::new(f),
+      transition: None,
+      inst_unit: TimeUnit::Secs,
+      dur_unit: TimeUnit::Secs
+    }
+  }
+
+  /// Whether the overlap applies at a given time: like cuts, from its *instance time* included to
+  /// its end excluded.
+  pub fn is_active(&self, t: Time) -> bool {
+    self.inst_time <= t && t < self.inst_time + self.dur
+  }
+
+  /// Normalized progress in the overlap at a given time.
+  pub fn progress(&self, t: Time) -> Time {
+    if self.dur > 0. { (t - self.inst_time) / self.dur } else { 0. }
+  }
+}
+
+/// A transition folds the active nodes of an overlap – in track order – given the normalized
+/// progress in the overlap.
+pub type Transition<'a> = Rc<Fn(Vec<Node>, Time) -> Node + 'a>;
+
+/// Mapping between transitions’ names and transitions, used to build the overlaps of a
+/// `TimelineManifest`.
+///
+/// The following transitions are always available:
+///
+/// - `"crossfade"`: the nodes fade from one to the next
+/// - `"wipe"`: each node wipes the previous one, from left to right
+/// - `"additive"`: the nodes are added
+/// - `"over"`: each node is composed over the previous ones
+///
+/// With more than two nodes, `"crossfade"` and `"wipe"` go through them in track order, each pair
+/// taking an equal part of the overlap.
+///
+/// Other transitions – wipes in other directions, custom effects – are registered with
+/// `TransitionRegistry::register`.
+pub struct TransitionRegistry<'a> {
+  transitions: HashMap<String, Transition<'a>>
+}
+
+impl<'a> TransitionRegistry<'a> {
+  pub fn new() -> Self {
+    let mut registry = TransitionRegistry {
+      transitions: HashMap::new()
+    };
+
+    registry.register("crossfade", crossfade);
+    registry.register("wipe", wipe);
+    registry.register("additive", additive);
+    registry.register("over", over);
+
+    registry
+  }
+
+  /// Register a transition, replacing any transition with the same name.
+  pub fn register<F>(&mut self, name: &str, f: F) where F: 'a + Fn(Vec<Node>, Time) -> Node {
+    self.transitions.insert(name.to_owned(), Rc::new(f));
+  }
+
+  pub fn get(&self, name: &str) -> Option<&Transition<'a>> {
+    self.transitions.get(name)
+  }
+
+  /// Build an overlap using a registered transition.
+  pub fn overlap(&self, name: &str, inst_time: Time, dur: Time) -> Option<Overlap<'a>> {
+    self.get(name).map(|transition| {
+      let transition = transition.clone();
+      let mut overlap = Overlap::new(inst_time, dur, move |nodes, progress| (*transition)(nodes, progress));
+
+      overlap.transition = Some(name.to_owned());
+      overlap
+    })
+  }
+}
+
+impl<'a> Default for TransitionRegistry<'a> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+// Fold nodes from the first one.
+fn fold_nodes<'a, F>(nodes: Vec<Node<'a>>, f: F) -> Node<'a> where F: Fn(Node<'a>, Node<'a>) -> Node<'a> {
+  let mut nodes = nodes.into_iter();
+  let first = nodes.next().unwrap_or_else(|| Node::from(RGBA::new(0., 0., 0., 0.)));
+
+  nodes.fold(first, f)
+}
+
+// Apply a transition between two consecutive nodes, the progress being split evenly between the
+// pairs of nodes so that each node is the only one shown at some point.
+fn sequence_nodes<'a, F>(mut nodes: Vec<Node<'a>>, progress: Time, f: F) -> Node<'a> where F: Fn(Node<'a>, Node<'a>, f32) -> Node<'a> {
+  if nodes.len() < 2 {
+    return nodes.pop().unwrap_or_else(|| Node::from(RGBA::new(0., 0., 0., 0.)));
+  }
+
+  let s = progress.max(0.).min(1.) * (nodes.len() - 1) as Time;
+  let k = (s.floor() as usize).min(nodes.len() - 2);
+  let local = (s - k as Time) as f32;
+  let (a, b) = {
+    let mut pair = nodes.drain(k..k + 2);
+    (pair.next().unwrap(), pair.next().unwrap())
+  };
+
+  f(a, b, local)
+}
+
+fn crossfade<'a>(nodes: Vec<Node<'a>>, progress: Time) -> Node<'a> {
+  sequence_nodes(nodes, progress, |a, b, p| {
+    a * Node::from(RGBA::new(1. - p, 1. - p, 1. - p, 1. - p)) + b * Node::from(RGBA::new(p, p, p, p))
+  })
+}
+
+fn wipe<'a>(nodes: Vec<Node<'a>>, progress: Time) -> Node<'a> {
+  sequence_nodes(nodes, progress, |a, b, p| a.wipe(b, p, [1., 0.]))
+}
+
+fn additive<'a>(nodes: Vec<Node<'a>>, _: Time) -> Node<'a> {
+  fold_nodes(nodes, |a, b| a + b)
+}
+
+fn over<'a>(nodes: Vec<Node<'a>>, _: Time) -> Node<'a> {
+  fold_nodes(nodes, |a, b| b.over(a))
+}
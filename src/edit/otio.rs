@@ -0,0 +1,273 @@
+//! OpenTimelineIO interchange.
+//!
+//! Timelines are exchanged as OTIO JSON documents: a `Timeline.1` holding a `Stack.1` of video
+//! `Track.1`s. Cuts become `Clip.1`s named after their clips, with a source range starting at their
+//! *input time*; the space between them is filled with `Gap.1`s. Speed and reverse playback are
+//! `LinearTimeWarp.1` effects and muted tracks are disabled. Overlaps, markers and time remapping
+//! are not exchanged.
+
+use serde_json::{from_str, to_string_pretty};
+
+use resource::{LoadError, Result, SaveError};
+use edit::{CutManifest, FrameRate, Time, TimelineManifest, TrackManifest};
+use tempo::TimeSpec;
+
+/// Export a timeline manifest as an OTIO document.
+///
+/// Times are expressed in frames at `rate`. Musical times are converted with the tempo map of the
+/// manifest; cuts that cannot be resolved are left out, as well as cuts with a non-positive speed
+/// and cuts overlapping the previous one on their track.
+///
+/// Fail if the frame rate is null.
+pub fn to_otio(manifest: &TimelineManifest, name: &str, rate: FrameRate) -> ::std::result::Result<String, SaveError> {
+  if !rate.is_valid() {
+    return Err(SaveError::SerializationFailed(format!("invalid frame rate: {}/{}", rate.num, rate.den)));
+  }
+
+  let manifest = manifest.resolved();
+  let rate = rate.fps();
+  let tracks = manifest.tracks.iter().enumerate().map(|(i, track)| {
+    let mut children = Vec::new();
+    let mut cursor = 0.;
+
+    for cut in &track.cuts {
+      let (in_time, out_time, inst_time) = match (cut.in_time.secs(), cut.out_time.secs(), cut.inst_time.secs()) {
+        (Some(in_time), Some(out_time), Some(inst_time)) => (in_time, out_time, inst_time),
+        _ => {
+          warn!("the cut of {:?} is in musical time and cannot be exported", cut.clip);
+          continue;
+        }
+      };
+
+      if cut.speed <= 0. {
+        warn!("the cut of {:?} has a non-positive speed and cannot be exported", cut.clip);
+        continue;
+      }
+
+      if inst_time < cursor {
+        warn!("the cut of {:?} overlaps the previous one on track {} and cannot be exported", cut.clip, i);
+        continue;
+      }
+
+      if inst_time > cursor {
+        children.push(OTIOItemManifest::gap(cursor, inst_time - cursor, rate));
+      }
+
+      let dur = (out_time - in_time) / cut.speed;
+      let mut clip = OTIOItemManifest::gap(in_time, dur, rate);
+      clip.schema = "Clip.1".to_owned();
+      clip.name = cut.clip.clone();
+
+      if cut.speed != 1. || cut.reverse {
+        clip.effects.push(OTIOTimeWarpManifest {
+          schema: "LinearTimeWarp.1".to_owned(),
+          time_scalar: if cut.reverse { -cut.speed } else { cut.speed }
+        });
+      }
+
+      children.push(clip);
+      cursor = inst_time + dur;
+    }
+
+    OTIOTrackManifest {
+      schema: "Track.1".to_owned(),
+      name: format!("V{}", i + 1),
+      kind: "Video".to_owned(),
+      enabled: !track.muted,
+      children: children
+    }
+  }).collect();
+
+  let timeline = OTIOTimelineManifest {
+    schema: "Timeline.1".to_owned(),
+    name: name.to_owned(),
+    tracks: OTIOStackManifest {
+      schema: "Stack.1".to_owned(),
+      children: tracks
+    }
+  };
+
+  to_string_pretty(&timeline).map_err(|e| SaveError::SerializationFailed(format!("{:?}", e)))
+}
+
+/// Import a timeline manifest from an OTIO document.
+///
+/// Only video tracks are imported; items other than clips and gaps are ignored. Clips without a
+/// source range use the available range of their media; a clip or gap with neither is an error.
+pub fn from_otio(json: &str) -> Result<TimelineManifest> {
+  let timeline: OTIOTimelineManifest = from_str(json).map_err(|e| LoadError::ParseFailed(format!("{:?}", e)))?;
+  let mut manifest = TimelineManifest::default();
+
+  for track in timeline.tracks.children.into_iter().filter(|track| track.kind == "Video") {
+    let mut cuts = Vec::new();
+    let mut cursor = 0.;
+
+    for item in track.children {
+      if !item.schema.starts_with("Gap.") && !item.schema.starts_with("Clip.") {
+        continue;
+      }
+
+      let (start, dur) = match item.range() {
+        Some(range) => (range.start_time.secs(), range.duration.secs()),
+        None => return Err(LoadError::ParseFailed(format!("the item {:?} has no source range", item.name)))
+      };
+
+      if item.schema.starts_with("Gap.") {
+        cursor += dur;
+      } else if item.schema.starts_with("Clip.") {
+        let time_scalar = item.effects.iter().filter(|effect| effect.schema.starts_with("LinearTimeWarp.")).fold(1., |s: Time, effect| s * effect.time_scalar);
+
+        if time_scalar == 0. {
+          return Err(LoadError::ParseFailed(format!("freeze frames are not supported: {:?}", item.name)));
+        }
+
+        cuts.push(CutManifest {
+          in_time: TimeSpec::Secs(start),
+          out_time: TimeSpec::Secs(start + dur * time_scalar.abs()),
+          inst_time: TimeSpec::Secs(cursor),
+          clip: item.name,
+          speed: time_scalar.abs(),
+          reverse: time_scalar < 0.,
+          remap: None
+        });
+
+        cursor += dur;
+      }
+    }
+
+    manifest.tracks.push(TrackManifest {
+      cuts: cuts,
+      muted: !track.enabled,
+      solo: false
+    });
+  }
+
+  Ok(manifest)
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct OTIOTimelineManifest {
+  #[serde(rename = "OTIO_SCHEMA")]
+  schema: String,
+  #[serde(default)]
+  name: String,
+  tracks: OTIOStackManifest
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct OTIOStackManifest {
+  #[serde(rename = "OTIO_SCHEMA")]
+  schema: String,
+  #[serde(default)]
+  children: Vec<OTIOTrackManifest>
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct OTIOTrackManifest {
+  #[serde(rename = "OTIO_SCHEMA")]
+  schema: String,
+  #[serde(default)]
+  name: String,
+  #[serde(default = "def_kind")]
+  kind: String,
+  #[serde(default = "def_enabled")]
+  enabled: bool,
+  #[serde(default)]
+  children: Vec<OTIOItemManifest>
+}
+
+fn def_kind() -> String {
+  "Video".to_owned()
+}
+
+fn def_enabled() -> bool {
+  true
+}
+
+// A clip or a gap.
+#[derive(Debug, Deserialize, Serialize)]
+struct OTIOItemManifest {
+  #[serde(rename = "OTIO_SCHEMA")]
+  schema: String,
+  #[serde(default)]
+  name: String,
+  #[serde(default)]
+  source_range: Option<OTIOTimeRangeManifest>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  media_reference: Option<OTIOMediaReferenceManifest>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  effects: Vec<OTIOTimeWarpManifest>
+}
+
+impl OTIOItemManifest {
+  fn gap(start: Time, dur: Time, rate: Time) -> Self {
+    OTIOItemManifest {
+      schema: "Gap.1".to_owned(),
+      name: String::new(),
+      source_range: Some(OTIOTimeRangeManifest {
+        schema: "TimeRange.1".to_owned(),
+        start_time: OTIORationalTimeManifest::new(start, rate),
+        duration: OTIORationalTimeManifest::new(dur, rate)
+      }),
+      media_reference: None,
+      effects: Vec::new()
+    }
+  }
+
+  // Range of the media used by the item: its source range, or the whole media if missing.
+  fn range(&self) -> Option<&OTIOTimeRangeManifest> {
+    self.source_range.as_ref().or_else(|| self.media_reference.as_ref().and_then(|media| media.available_range.as_ref()))
+  }
+}
+
+// Media of a clip; only its available range is used.
+#[derive(Debug, Deserialize, Serialize)]
+struct OTIOMediaReferenceManifest {
+  #[serde(rename = "OTIO_SCHEMA")]
+  schema: String,
+  #[serde(default)]
+  available_range: Option<OTIOTimeRangeManifest>
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct OTIOTimeRangeManifest {
+  #[serde(rename = "OTIO_SCHEMA")]
+  schema: String,
+  start_time: OTIORationalTimeManifest,
+  duration: OTIORationalTimeManifest
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct OTIORationalTimeManifest {
+  #[serde(rename = "OTIO_SCHEMA")]
+  schema: String,
+  value: Time,
+  rate: Time
+}
+
+impl OTIORationalTimeManifest {
+  fn new(t: Time, rate: Time) -> Self {
+    OTIORationalTimeManifest {
+      schema: "RationalTime.1".to_owned(),
+      value: t * rate,
+      rate: rate
+    }
+  }
+
+  fn secs(&self) -> Time {
+    if self.rate == 0. { 0. } else { self.value / self.rate }
+  }
+}
+
+// Effects other than time warps are read with a neutral scalar.
+#[derive(Debug, Deserialize, Serialize)]
+struct OTIOTimeWarpManifest {
+  #[serde(rename = "OTIO_SCHEMA")]
+  schema: String,
+  #[serde(default = "def_time_scalar")]
+  time_scalar: Time
+}
+
+fn def_time_scalar() -> Time {
+  1.
+}
@@ -24,9 +24,10 @@
 //!   simple types that can be used to interact with the demo, such as initialization, default
 //!   event handling, and so on
 //! - **camera**: provides some camera features for both release and debugging purposes
-//! - **color**: color types
+//! - **color**: color types and color spaces (sRGB, HSV, Oklab)
 //! - **edit**: everything you need to edit your demo – it provides types and functions to reason
-//!   about timelines, tracks, cuts and clips, hence easing the overall making of a demo
+//!   about timelines, tracks, cuts and clips, hence easing the overall making of a demo; edits
+//!   can be exchanged as EDL or OpenTimelineIO
 //! - **gui**: this module provides some GUI code that you can use to build nice debugging
 //!   interfaces – up to now, it’s not designed for release code, but it might be at some time
 //! - **linear**: linear algebra
@@ -37,6 +38,7 @@
 //! - **overlay**: this module provides 2D primitives and rendering functions
 //! - **projection**: projection trait and functions
 //! - **shader**: provides 
+//! - **tempo**: musical time – tempo maps converting between seconds, beats and bars
 //! - **extra**: some extra (but not mandatory) other modules
 
 #![feature(associated_consts)]
@@ -82,6 +84,7 @@ pub mod projection;
 pub mod resource;
 pub mod shader;
 pub mod spline;
+pub mod tempo;
 pub mod text;
 pub mod texture;
 pub mod transform;
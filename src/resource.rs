@@ -3,8 +3,9 @@
 use any_cache::{Cache, HashCache};
 use notify::{self, RecommendedWatcher, Watcher};
 use std::any::Any;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::fs::create_dir_all;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
@@ -38,6 +39,35 @@ impl<T> Reload for T where T: Load<Args=()> {
   }
 }
 
+/// Class of types that can be saved.
+///
+/// A saved resource must be loadable back with `Load::load`.
+pub trait Save: Load {
+  fn save<P>(&self, path: P) -> ::std::result::Result<(), SaveError> where P: AsRef<Path>;
+}
+
+/// Save a resource at the path it would be loaded from with `ResCache::get`.
+pub fn save<T>(resource: &T, key: &str) -> ::std::result::Result<(), SaveError> where T: Save {
+  let path = resource_path::<T>(key);
+
+  if let Some(parent) = path.parent() {
+    create_dir_all(parent).map_err(|e| SaveError::FileCreationFailed(parent.to_path_buf(), format!("{:?}", e)))?;
+  }
+
+  resource.save(path)
+}
+
+// Path of a resource in the data directory, used both to load and save it.
+fn resource_path<T>(key: &str) -> PathBuf where T: Load {
+  Path::new("data").join(T::TY_STR).join(key)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SaveError {
+  FileCreationFailed(PathBuf, String),
+  SerializationFailed(String)
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum LoadError {
   FileNotFound(PathBuf, String),
@@ -48,22 +78,36 @@ pub enum LoadError {
 pub type Result<T> = ::std::result::Result<T, LoadError>;
 
 /// Resources are wrapped in this type.
+///
+/// Along with the resource, a `Res` holds its *generation*, which is incremented every time the
+/// resource is reloaded by the `ResCache`.
 #[derive(Debug)]
-pub struct Res<T>(Rc<RefCell<T>>);
+pub struct Res<T> {
+  resource: Rc<RefCell<T>>,
+  generation: Rc<Cell<u64>>
+}
 
 impl<T> Res<T> {
   pub fn new(resource: T) -> Self {
-    Res(Rc::new(RefCell::new(resource)))
+    Res::from(Rc::new(RefCell::new(resource)))
   }
 
   pub fn as_rc(&self) -> &Rc<RefCell<T>> {
-    &self.0
+    &self.resource
+  }
+
+  /// Number of times the resource has been reloaded.
+  pub fn generation(&self) -> u64 {
+    self.generation.get()
   }
 }
 
 impl<T> Clone for Res<T> {
   fn clone(&self) -> Self {
-    Res(self.0.clone())
+    Res {
+      resource: self.resource.clone(),
+      generation: self.generation.clone()
+    }
   }
 }
 
@@ -71,13 +115,16 @@ impl<T> Deref for Res<T> {
   type Target = RefCell<T>;
 
   fn deref(&self) -> &Self::Target {
-    &self.0
+    &self.resource
   }
 }
 
 impl<T> From<Rc<RefCell<T>>> for Res<T> {
   fn from(rc: Rc<RefCell<T>>) -> Self {
-    Res(rc)
+    Res {
+      resource: rc,
+      generation: Rc::new(Cell::new(0))
+    }
   }
 }
 
@@ -85,11 +132,19 @@ impl<T> From<Rc<RefCell<T>>> for Res<T> {
 const UPDATE_AWAIT_TIME_MS: u64 = 1000;
 
 /// Resource cache. Responsible for caching resource.
+///
+/// Resources got from the cache while loading another resource are its dependencies: when a
+/// dependency is reloaded, the resources depending on it are reloaded as well. A resource cannot
+/// depend on itself, even indirectly.
 pub struct ResCache {
   // contains all the typed-erased Rc<RefCell<T>>
   cache: HashCache<PathBuf>,
   // contains all metadata on resources
   metadata: HashMap<PathBuf, ResMetaData>,
+  // paths of the resources being loaded, each one being loaded by the previous one
+  loading: Vec<PathBuf>,
+  // paths of the resources depending on a resource, by path
+  dependents: HashMap<PathBuf, Vec<PathBuf>>,
   // vector of pair (path, timestamp) giving indication on resources to reload
   dirty: Arc<Mutex<Vec<(PathBuf, Instant)>>>,
   #[allow(dead_code)]
@@ -124,6 +179,8 @@ impl ResCache {
     ResCache {
       cache: HashCache::new(),
       metadata: HashMap::new(),
+      loading: Vec::new(),
+      dependents: HashMap::new(),
       dirty: dirty,
       watcher_thread: join_handle
     }
@@ -131,32 +188,54 @@ impl ResCache {
 
   /// Get a resource from the cache.
   pub fn get<T>(&mut self, key: &str, args: T::Args) -> Option<Res<T>> where T: 'static + Any + Reload {
-    let path_str = format!("data/{}/{}", T::TY_STR, key);
-    let path = Path::new(&path_str);
-    let path_buf = path.to_owned();
+    let path = resource_path::<T>(key);
+    let path_buf = path.clone();
+
+    // the resource being loaded, if any, depends on this one
+    if let Some(dependent) = self.loading.last().cloned() {
+      let dependents = self.dependents.entry(path_buf.clone()).or_insert_with(Vec::new);
+
+      if !dependents.contains(&dependent) {
+        dependents.push(dependent);
+      }
+    }
+
+    if self.loading.contains(&path_buf) {
+      err!("resource at {} depends on itself", path.display());
+      return None;
+    }
 
     match self.cache.get::<Res<T>>(&path_buf).cloned() {
       r@Some(..) => {
-        deb!("cache hit for {} ({})", key, path_str);
+        deb!("cache hit for {} ({})", key, path.display());
         r
       },
       None => {
-        deb!("cache miss for {} ({})", key, path_str);
+        deb!("cache miss for {} ({})", key, path.display());
 
         // specific loading
         if path.exists() {
-          match T::load(&path, self, args.clone()) {
+          self.loading.push(path_buf.clone());
+          let loaded = T::load(&path, self, args.clone());
+          self.loading.pop();
+
+          match loaded {
             Ok(resource) => {
-              let res = Res(Rc::new(RefCell::new(resource)));
+              let res = Res::new(resource);
               let res_ = res.clone();
 
               let path_buf_ = path_buf.clone();
               // closure used to reload the object when needed
               let on_reload: Box<for<'a> Fn(&'a mut ResCache)> = Box::new(move |cache_| {
-                match T::load(&path_buf_, cache_, args.clone()) {
+                cache_.loading.push(path_buf_.clone());
+                let reloaded = T::load(&path_buf_, cache_, args.clone());
+                cache_.loading.pop();
+
+                match reloaded {
                   Ok(new_resource) => {
                     // replace the current resource with the freshly loaded one
                     *res_.borrow_mut() = new_resource;
+                    res_.generation.set(res_.generation() + 1);
                     deb!("reloaded resource from {:?}", path_buf_);
                   },
                   Err(e) => {
@@ -177,18 +256,23 @@ impl ResCache {
               Some(res)
             },
             Err(e) => {
-              err!("unable to load resource from {}:\n{:#?}", path_str, e);
+              err!("unable to load resource from {}:\n{:#?}", path.display(), e);
               None
             }
           }
         } else { // path doesn’t exist
-          err!("resource at {} cannot be found", path_str);
+          err!("resource at {} cannot be found", path.display());
           None
         }
       }
     }
   }
 
+  /// Whether a resource is being loaded – i.e. getting it now would make it depend on itself.
+  pub fn is_loading<T>(&self, key: &str) -> bool where T: Load {
+    self.loading.contains(&resource_path::<T>(key))
+  }
+
   /// Synchronize the cache by updating the resource that ought to.
   pub fn sync(&mut self) {
     let dirty = self.dirty.clone();
@@ -196,15 +280,50 @@ impl ResCache {
 
     for &(ref path, ref instant) in dirty_.iter() {
       let mut metadata = self.metadata.remove(path).unwrap();
+      let reload = instant.duration_since(metadata.last_update_instant) >= Duration::from_millis(UPDATE_AWAIT_TIME_MS);
 
-      if instant.duration_since(metadata.last_update_instant) >= Duration::from_millis(UPDATE_AWAIT_TIME_MS) {
+      if reload {
         (metadata.on_reload)(self);
       }
 
       metadata.last_update_instant = *instant;
       self.metadata.insert(path.clone(), metadata);
+
+      if reload {
+        self.reload_dependents(path);
+      }
     }
 
     dirty_.clear();
   }
+
+  // Reload all the resources depending – even indirectly – on a resource, closest ones first.
+  fn reload_dependents(&mut self, path: &Path) {
+    let mut dependents: Vec<PathBuf> = Vec::new();
+    let mut i = 0;
+    let mut current = path.to_owned();
+
+    loop {
+      for dependent in self.dependents.get(&current).cloned().unwrap_or_else(Vec::new) {
+        if dependent.as_path() != path && !dependents.contains(&dependent) {
+          dependents.push(dependent);
+        }
+      }
+
+      if i == dependents.len() {
+        break;
+      }
+
+      current = dependents[i].clone();
+      i += 1;
+    }
+
+    for dependent in dependents {
+      if let Some(metadata) = self.metadata.remove(&dependent) {
+        deb!("reloading {:?}, which depends on {:?}", dependent, path);
+        (metadata.on_reload)(self);
+        self.metadata.insert(dependent, metadata);
+      }
+    }
+  }
 }
@@ -0,0 +1,192 @@
+//! Multi-channel animation clips.
+//!
+//! An animation clip gathers several named splines – called *channels* – of possibly different
+//! types in a single resource. That enables animating all the parameters of an object – e.g. its
+//! position, orientation and scale – out of a single file, keeping them in sync when it’s reloaded.
+//!
+//! On disk, a clip is an object mapping each channel name to a channel type and the spline itself,
+//! in the same format as for `Spline`:
+//!
+//! ```json
+//! {
+//!   "position": { "vec3": [ { "t": 0, "value": [0, 0, 0] }, { "t": 1, "value": [0, 1, 0] } ] },
+//!   "scale": { "scale": { "keys": [ { "t": 0, "value": [1, 1, 1] } ], "post": "constant" } }
+//! }
+//! ```
+//!
+//! Available channel types are `float`, `vec2`, `vec3`, `vec4`, `orientation` and `scale`.
+
+use serde_json::from_reader;
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::fs::File;
+use std::path::Path;
+
+use linear::{Scale, UnitQuaternion, Vector2, Vector3, Vector4};
+use resource::{Load, LoadError, ResCache};
+use spline::{Interpolate, Spline, SplineFile, Time, spline_from_file};
+
+/// Typed animation channel.
+#[derive(Clone, Debug)]
+pub enum Channel {
+  Float(Spline<f32>),
+  Vec2(Spline<Vector2<f32>>),
+  Vec3(Spline<Vector3<f32>>),
+  Vec4(Spline<Vector4<f32>>),
+  Orientation(Spline<UnitQuaternion<f32>>),
+  Scale(Spline<Scale>)
+}
+
+impl Channel {
+  /// Sample the channel at a given time.
+  pub fn sample(&self, t: Time) -> Option<ChannelValue> {
+    match *self {
+      Channel::Float(ref spline) => spline.sample(t).map(ChannelValue::Float),
+      Channel::Vec2(ref spline) => spline.sample(t).map(ChannelValue::Vec2),
+      Channel::Vec3(ref spline) => spline.sample(t).map(ChannelValue::Vec3),
+      Channel::Vec4(ref spline) => spline.sample(t).map(ChannelValue::Vec4),
+      Channel::Orientation(ref spline) => spline.sample(t).map(ChannelValue::Orientation),
+      Channel::Scale(ref spline) => spline.sample(t).map(ChannelValue::Scale)
+    }
+  }
+}
+
+/// Value of a channel at a given time.
+#[derive(Clone, Copy, Debug)]
+pub enum ChannelValue {
+  Float(f32),
+  Vec2(Vector2<f32>),
+  Vec3(Vector3<f32>),
+  Vec4(Vector4<f32>),
+  Orientation(UnitQuaternion<f32>),
+  Scale(Scale)
+}
+
+/// Types that can be used as channels.
+pub trait ChannelType: Sized {
+  /// Get the spline of a channel if it has the right type.
+  fn from_channel(channel: &Channel) -> Option<&Spline<Self>>;
+}
+
+macro_rules! impl_channel_type {
+  ($t:ty, $variant:ident) => {
+    impl ChannelType for $t {
+      fn from_channel(channel: &Channel) -> Option<&Spline<Self>> {
+        match *channel {
+          Channel::$variant(ref spline) => Some(spline),
+          _ => None
+        }
+      }
+    }
+  }
+}
+
+impl_channel_type!(f32, Float);
+impl_channel_type!(Vector2<f32>, Vec2);
+impl_channel_type!(Vector3<f32>, Vec3);
+impl_channel_type!(Vector4<f32>, Vec4);
+impl_channel_type!(UnitQuaternion<f32>, Orientation);
+impl_channel_type!(Scale, Scale);
+
+/// Animation clip, made of named channels.
+#[derive(Clone, Debug)]
+pub struct AnimationClip {
+  channels: HashMap<String, Channel>
+}
+
+impl AnimationClip {
+  pub fn new() -> Self {
+    AnimationClip {
+      channels: HashMap::new()
+    }
+  }
+
+  /// Add a channel, replacing the one with the same name if any – which is returned.
+  pub fn add_channel<N>(&mut self, name: N, channel: Channel) -> Option<Channel> where N: Into<String> {
+    self.channels.insert(name.into(), channel)
+  }
+
+  /// Get a channel by its name.
+  pub fn channel(&self, name: &str) -> Option<&Channel> {
+    self.channels.get(name)
+  }
+
+  /// Iterate over all the channels and their names.
+  pub fn channels(&self) -> hash_map::Iter<String, Channel> {
+    self.channels.iter()
+  }
+
+  /// Get the spline of a channel by its name, if it has the requested type.
+  pub fn spline<T>(&self, name: &str) -> Option<&Spline<T>> where T: ChannelType {
+    self.channels.get(name).and_then(T::from_channel)
+  }
+
+  /// Sample a channel by its name at a given time, if it has the requested type.
+  pub fn sample_channel<T>(&self, name: &str, t: Time) -> Option<T> where T: ChannelType + Interpolate {
+    self.spline::<T>(name).and_then(|spline| spline.sample(t))
+  }
+
+  /// Sample all the channels at a given time.
+  ///
+  /// Channels that cannot be sampled at that time are absent from the result.
+  pub fn sample(&self, t: Time) -> HashMap<String, ChannelValue> {
+    self.channels.iter().filter_map(|(name, channel)| channel.sample(t).map(|value| (name.clone(), value))).collect()
+  }
+}
+
+impl Default for AnimationClip {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Load for AnimationClip {
+  type Args = ();
+
+  const TY_STR: &'static str = "animations";
+
+  fn load<P>(path: P, cache: &mut ResCache, _: Self::Args) -> Result<Self, LoadError> where P: AsRef<Path> {
+    let path = path.as_ref();
+
+    info!("loading animation clip: {:?}", path);
+
+    let file = File::open(path).map_err(|e| LoadError::FileNotFound(path.to_path_buf(), format!("{:?}", e)))?;
+    let manifest: HashMap<String, ChannelManifest> = from_reader(file).map_err(|e| LoadError::ParseFailed(format!("{:?}", e)))?;
+
+    let mut channels = HashMap::new();
+
+    for (name, channel) in manifest {
+      let channel = match channel {
+        ChannelManifest::Float(f) => Channel::Float(spline_from_file(f, cache)?),
+        ChannelManifest::Vec2(f) => Channel::Vec2(spline_from_file(f, cache)?),
+        ChannelManifest::Vec3(f) => Channel::Vec3(spline_from_file(f, cache)?),
+        ChannelManifest::Vec4(f) => Channel::Vec4(spline_from_file(f, cache)?),
+        ChannelManifest::Orientation(f) => Channel::Orientation(spline_from_file(f, cache)?),
+        ChannelManifest::Scale(f) => Channel::Scale(spline_from_file(f, cache)?)
+      };
+
+      channels.insert(name, channel);
+    }
+
+    Ok(AnimationClip {
+      channels: channels
+    })
+  }
+}
+
+// Channel as stored on disk.
+#[derive(Deserialize)]
+enum ChannelManifest {
+  #[serde(rename = "float")]
+  Float(SplineFile<f32>),
+  #[serde(rename = "vec2")]
+  Vec2(SplineFile<[f32; 2]>),
+  #[serde(rename = "vec3")]
+  Vec3(SplineFile<[f32; 3]>),
+  #[serde(rename = "vec4")]
+  Vec4(SplineFile<[f32; 4]>),
+  #[serde(rename = "orientation")]
+  Orientation(SplineFile<[f32; 4]>),
+  #[serde(rename = "scale")]
+  Scale(SplineFile<[f32; 3]>)
+}
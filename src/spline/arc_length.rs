@@ -0,0 +1,201 @@
+//! Arc-length reparametrization of splines.
+//!
+//! Splines are sampled by time, so an object following a spline goes faster where keys are far
+//! from each other and slower where they’re close. An `ArcLength` table maps the time of a spline
+//! to the distance travelled along it, which makes it possible to sample the spline by distance
+//! instead.
+
+use std::cmp::max;
+
+use linear::{Vector2, Vector3};
+use spline::{Extrapolation, Interpolate, Interpolation, Key, Spline, Time};
+
+/// Values with a distance between them.
+pub trait Metric {
+  /// Distance between two values.
+  fn distance(a: &Self, b: &Self) -> f32;
+}
+
+impl Metric for f32 {
+  fn distance(a: &Self, b: &Self) -> f32 {
+    (a - b).abs()
+  }
+}
+
+impl Metric for Vector2<f32> {
+  fn distance(a: &Self, b: &Self) -> f32 {
+    let d = *a - *b;
+    (d.x * d.x + d.y * d.y).sqrt()
+  }
+}
+
+impl Metric for Vector3<f32> {
+  fn distance(a: &Self, b: &Self) -> f32 {
+    let d = *a - *b;
+    (d.x * d.x + d.y * d.y + d.z * d.z).sqrt()
+  }
+}
+
+/// Arc-length table of a spline.
+///
+/// The table is built by sampling the spline and summing the lengths of the chords between the
+/// samples. It doesn’t borrow the spline, so it must be rebuilt whenever the spline changes.
+#[derive(Clone, Debug)]
+pub struct ArcLength {
+  // pairs of (time, distance from the first sample), both non-decreasing
+  table: Vec<(Time, f32)>
+}
+
+impl ArcLength {
+  /// Build the arc-length table of a spline by sampling each segment between two keys
+  /// `samples_per_segment` times.
+  ///
+  /// Times at which the spline cannot be sampled – e.g. the boundary segments of a Catmull-Rom
+  /// spline – are skipped.
+  pub fn new<T>(spline: &Spline<T>, samples_per_segment: usize) -> Self where T: Interpolate + Metric {
+    let keys: Vec<&Key<T>> = spline.into_iter().collect();
+    let samples_per_segment = max(samples_per_segment, 1);
+    let mut table = Vec::new();
+    let mut prev: Option<T> = None;
+    let mut dist = 0.;
+
+    for (i, key) in keys.iter().enumerate() {
+      let times: Vec<Time> = match keys.get(i + 1) {
+        Some(next) => (0..samples_per_segment).map(|j| key.t + (next.t - key.t) * j as f32 / samples_per_segment as f32).collect(),
+        None => vec![key.t]
+      };
+
+      for t in times {
+        if let Some(value) = spline.clamped_sample(t) {
+          if let Some(ref p) = prev {
+            dist += T::distance(p, &value);
+          }
+
+          table.push((t, dist));
+          prev = Some(value);
+        }
+      }
+    }
+
+    ArcLength {
+      table: table
+    }
+  }
+
+  /// Total length of the spline.
+  pub fn length(&self) -> f32 {
+    self.table.last().map_or(0., |&(_, d)| d)
+  }
+
+  /// Distance travelled along the spline at a given time.
+  ///
+  /// The distance is clamped to `[0; self.length()]`.
+  pub fn distance_at(&self, t: Time) -> f32 {
+    let table = &self.table;
+
+    match table.binary_search_by(|&(ti, _)| ti.partial_cmp(&t).unwrap()) {
+      Ok(i) => table[i].1,
+      Err(0) => 0.,
+      Err(i) if i == table.len() => self.length(),
+      Err(i) => {
+        let (t0, d0) = table[i - 1];
+        let (t1, d1) = table[i];
+
+        d0 + (d1 - d0) * (t - t0) / (t1 - t0)
+      }
+    }
+  }
+
+  /// Time at which a given distance is travelled along the spline.
+  ///
+  /// The time is clamped to the times of the first and last samples of the table.
+  pub fn time_at(&self, d: f32) -> Time {
+    let table = &self.table;
+
+    if table.is_empty() {
+      return 0.;
+    }
+
+    match table.binary_search_by(|&(_, di)| di.partial_cmp(&d).unwrap()) {
+      Ok(i) => table[i].0,
+      Err(0) => table[0].0,
+      Err(i) if i == table.len() => table[i - 1].0,
+      Err(i) => {
+        let (t0, d0) = table[i - 1];
+        let (t1, d1) = table[i];
+
+        t0 + (t1 - t0) * (d - d0) / (d1 - d0)
+      }
+    }
+  }
+
+  /// Sample a spline at a given distance from its beginning.
+  ///
+  /// The table must have been built from the same spline.
+  pub fn sample_at_distance<T>(&self, spline: &Spline<T>, d: f32) -> Option<T> where T: Interpolate {
+    spline.clamped_sample(self.time_at(d))
+  }
+
+  /// Sample a spline at a normalized distance, `0` being its beginning and `1` its end.
+  ///
+  /// The table must have been built from the same spline.
+  pub fn sample_normalized<T>(&self, spline: &Spline<T>, u: f32) -> Option<T> where T: Interpolate {
+    self.sample_at_distance(spline, u * self.length())
+  }
+
+  /// Sample a spline at a given time as if it was travelled at constant speed from its beginning.
+  ///
+  /// The table must have been built from the same spline.
+  pub fn sample_at_speed<T>(&self, spline: &Spline<T>, speed: f32, t: Time) -> Option<T> where T: Interpolate {
+    self.sample_at_distance(spline, speed * t)
+  }
+
+  /// Sample a spline at a given time, following a distance curve – see `speed_to_distance`.
+  ///
+  /// The table must have been built from the same spline.
+  pub fn sample_along<T>(&self, spline: &Spline<T>, distance: &Spline<f32>, t: Time) -> Option<T> where T: Interpolate {
+    distance.sample(t).and_then(|d| self.sample_at_distance(spline, d))
+  }
+}
+
+/// Turn a speed curve into a distance curve by integrating it with a fixed time step.
+///
+/// The resulting spline is linear and holds its end values; its first key has a distance of `0`.
+/// Sample it to get the distance to pass to `ArcLength::sample_at_distance`, or use it directly with
+/// `ArcLength::sample_along`.
+///
+/// # Return
+///
+/// `None` if `step` is not strictly positive.
+pub fn speed_to_distance(speed: &Spline<f32>, step: Time) -> Option<Spline<f32>> {
+  if !(step > 0.) {
+    return None;
+  }
+
+  let keys: Vec<&Key<f32>> = speed.into_iter().collect();
+  let mut distance_keys = Vec::new();
+
+  if let (Some(first), Some(last)) = (keys.first(), keys.last()) {
+    // times are computed from the index of the step so that small steps don’t get lost in rounding
+    let steps = ((last.t - first.t) / step).ceil() as usize;
+    let mut t = first.t;
+    let mut v = first.value;
+    let mut dist = 0.;
+
+    distance_keys.push(Key::new(t, dist, Interpolation::Linear));
+
+    for i in 1..steps + 1 {
+      let t1 = (first.t + i as Time * step).min(last.t);
+      let v1 = speed.clamped_sample(t1).unwrap_or(v);
+
+      // trapezoidal rule
+      dist += (v + v1) * 0.5 * (t1 - t);
+      distance_keys.push(Key::new(t1, dist, Interpolation::Linear));
+
+      t = t1;
+      v = v1;
+    }
+  }
+
+  Some(Spline::from_keys(distance_keys).with_extrapolation(Extrapolation::Constant, Extrapolation::Constant))
+}
@@ -0,0 +1,143 @@
+//! Easing functions.
+//!
+//! Easing functions map a normalized time in `[0; 1]` to a normalized progression. They all start
+//! at `0` and end at `1`, but some of them – *back* and *elastic* – overshoot in between.
+
+use std::f32::consts;
+
+/// Easing function.
+///
+/// *In* variants accelerate from the start, *out* variants decelerate to the end and *in-out*
+/// variants do both.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Ease {
+  #[serde(rename = "quad_in")]
+  QuadIn,
+  #[serde(rename = "quad_out")]
+  QuadOut,
+  #[serde(rename = "quad_in_out")]
+  QuadInOut,
+  #[serde(rename = "cubic_in")]
+  CubicIn,
+  #[serde(rename = "cubic_out")]
+  CubicOut,
+  #[serde(rename = "cubic_in_out")]
+  CubicInOut,
+  #[serde(rename = "expo_in")]
+  ExpoIn,
+  #[serde(rename = "expo_out")]
+  ExpoOut,
+  #[serde(rename = "expo_in_out")]
+  ExpoInOut,
+  #[serde(rename = "back_in")]
+  BackIn,
+  #[serde(rename = "back_out")]
+  BackOut,
+  #[serde(rename = "back_in_out")]
+  BackInOut,
+  #[serde(rename = "elastic_in")]
+  ElasticIn,
+  #[serde(rename = "elastic_out")]
+  ElasticOut,
+  #[serde(rename = "elastic_in_out")]
+  ElasticInOut,
+  #[serde(rename = "bounce_in")]
+  BounceIn,
+  #[serde(rename = "bounce_out")]
+  BounceOut,
+  #[serde(rename = "bounce_in_out")]
+  BounceInOut
+}
+
+// Overshoot of the back easing functions.
+const BACK_OVERSHOOT: f32 = 1.70158;
+
+impl Ease {
+  /// Apply the easing function to a normalized time.
+  pub fn apply(self, t: f32) -> f32 {
+    match self {
+      Ease::QuadIn => t * t,
+      Ease::QuadOut => 1. - (1. - t) * (1. - t),
+      Ease::QuadInOut => in_out(t, |t| t * t),
+      Ease::CubicIn => t * t * t,
+      Ease::CubicOut => 1. - (1. - t).powi(3),
+      Ease::CubicInOut => in_out(t, |t| t * t * t),
+      Ease::ExpoIn => expo_in(t),
+      Ease::ExpoOut => 1. - expo_in(1. - t),
+      Ease::ExpoInOut => in_out(t, expo_in),
+      Ease::BackIn => back_in(t),
+      Ease::BackOut => 1. - back_in(1. - t),
+      Ease::BackInOut => in_out(t, back_in),
+      Ease::ElasticIn => elastic_in(t),
+      Ease::ElasticOut => 1. - elastic_in(1. - t),
+      Ease::ElasticInOut => in_out(t, elastic_in),
+      Ease::BounceIn => 1. - bounce_out(1. - t),
+      Ease::BounceOut => bounce_out(t),
+      Ease::BounceInOut => in_out(t, |t| 1. - bounce_out(1. - t))
+    }
+  }
+
+  /// Derivative of order 1 or 2 of the easing function at a normalized time.
+  ///
+  /// The derivative is approximated with finite differences.
+  pub fn derivative(self, t: f32, order: u8) -> f32 {
+    let h = 1e-3;
+    // keep the samples in [0; 1], where the easing functions are defined
+    let t = t.max(h).min(1. - h);
+
+    if order == 1 {
+      (self.apply(t + h) - self.apply(t - h)) / (2. * h)
+    } else {
+      (self.apply(t + h) - 2. * self.apply(t) + self.apply(t - h)) / (h * h)
+    }
+  }
+}
+
+// Build an in-out easing function out of an in one.
+fn in_out<F>(t: f32, ease_in: F) -> f32 where F: Fn(f32) -> f32 {
+  if t < 0.5 {
+    ease_in(2. * t) * 0.5
+  } else {
+    1. - ease_in(2. - 2. * t) * 0.5
+  }
+}
+
+fn expo_in(t: f32) -> f32 {
+  if t <= 0. {
+    0.
+  } else {
+    2f32.powf(10. * t - 10.)
+  }
+}
+
+fn back_in(t: f32) -> f32 {
+  t * t * ((BACK_OVERSHOOT + 1.) * t - BACK_OVERSHOOT)
+}
+
+fn elastic_in(t: f32) -> f32 {
+  if t <= 0. {
+    0.
+  } else if t >= 1. {
+    1.
+  } else {
+    -2f32.powf(10. * t - 10.) * ((10. * t - 10.75) * 2. * consts::PI / 3.).sin()
+  }
+}
+
+fn bounce_out(t: f32) -> f32 {
+  let n = 7.5625;
+  let d = 2.75;
+
+  if t < 1. / d {
+    n * t * t
+  } else if t < 2. / d {
+    let t = t - 1.5 / d;
+    n * t * t + 0.75
+  } else if t < 2.5 / d {
+    let t = t - 2.25 / d;
+    n * t * t + 0.9375
+  } else {
+    let t = t - 2.625 / d;
+    n * t * t + 0.984375
+  }
+}
@@ -0,0 +1,84 @@
+//! Spline fitting.
+//!
+//! Recorded data – camera moves, audio envelopes, etc. – often come as dense samples. Fitting turns
+//! them into a spline with as few keys as possible while staying close to the samples.
+
+use std::f32;
+
+use spline::{Interpolate, Interpolation, Key, Metric, Spline, Time};
+
+/// Fit a spline to `(time, value)` samples, so that no sample is more than `max_error` away from the
+/// spline.
+///
+/// Keys are picked among the samples: starting with the first and last samples, the worst sample of
+/// every segment exceeding the error is turned into a key, until every sample is close enough.
+/// Samples don’t have to be sorted; samples sharing the same time are only considered once.
+///
+/// Every key uses the given interpolation mode. Since `Interpolation::CatmullRom` cannot be sampled
+/// on the first and last segments of a spline, two extra keys are added in that case: one before the
+/// first sample and one after the last sample, holding their values.
+pub fn fit_spline<T>(samples: &[(Time, T)], max_error: f32, interpolation: Interpolation) -> Spline<T> where T: Interpolate + Metric {
+  let mut samples = samples.to_vec();
+  samples.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+  samples.dedup_by(|a, b| a.0 == b.0);
+
+  let n = samples.len();
+
+  if n < 2 {
+    return build_spline(&samples, &(0..n).collect::<Vec<_>>(), interpolation);
+  }
+
+  let mut indices = vec![0, n - 1];
+
+  loop {
+    let spline = build_spline(&samples, &indices, interpolation);
+    let mut new_indices = Vec::new();
+
+    for segment in indices.windows(2) {
+      let mut worst = None;
+      let mut worst_error = max_error;
+
+      for i in segment[0] + 1 .. segment[1] {
+        let (t, ref value) = samples[i];
+        let error = spline.sample(t).map_or(f32::INFINITY, |v| T::distance(&v, value));
+
+        if error > worst_error {
+          worst = Some(i);
+          worst_error = error;
+        }
+      }
+
+      if let Some(i) = worst {
+        new_indices.push(i);
+      }
+    }
+
+    if new_indices.is_empty() {
+      return spline;
+    }
+
+    indices.extend(new_indices);
+    indices.sort();
+  }
+}
+
+// Build a spline with the samples at the given sorted indices.
+fn build_spline<T>(samples: &[(Time, T)], indices: &[usize], interpolation: Interpolation) -> Spline<T> where T: Copy {
+  let mut keys: Vec<_> = indices.iter().map(|&i| Key::new(samples[i].0, samples[i].1, interpolation)).collect();
+
+  let catmull_rom = match interpolation {
+    Interpolation::CatmullRom => true,
+    _ => false
+  };
+
+  if catmull_rom && keys.len() >= 2 {
+    let len = keys.len();
+    let (first, second) = (keys[0], keys[1]);
+    let (before_last, last) = (keys[len - 2], keys[len - 1]);
+
+    keys.insert(0, Key::new(2. * first.t - second.t, first.value, interpolation));
+    keys.push(Key::new(2. * last.t - before_last.t, last.value, interpolation));
+  }
+
+  Spline::from_keys(keys)
+}
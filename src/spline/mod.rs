@@ -0,0 +1,1440 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as DeError;
+use serde_json::{from_reader, to_writer_pretty};
+use std::f32::consts;
+use std::fs::File;
+use std::mem::replace;
+use std::ops::{Add, Div, Mul, Sub};
+use std::path::Path;
+
+use color::{ColorSpace, blend_rgb, blend_rgba};
+use linear::{BaseFloat, Quaternion, Scale, Unit, UnitQuaternion, Vector2, Vector3, Vector4};
+use resource::{Load, LoadError, ResCache, Save, SaveError};
+use tempo::TempoMap;
+
+pub mod animation;
+pub mod arc_length;
+pub mod ease;
+pub mod fit;
+
+pub use self::animation::{AnimationClip, Channel, ChannelType, ChannelValue};
+pub use self::arc_length::{ArcLength, Metric, speed_to_distance};
+pub use self::ease::Ease;
+pub use self::fit::fit_spline;
+
+/// Time used as sampling type in splines.
+pub type Time = f32;
+
+/// A spline control point.
+///
+/// This type associates a value at a given time. It also contains an interpolation object used to
+/// determine how to interpolate values on the segment defined by this key and the next one.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Key<T> {
+  /// Time at which the `Key` should be reached.
+  pub t: Time,
+  /// Actual value.
+  pub value: T,
+  /// Interpolation mode.
+  #[serde(default)]
+  pub interpolation: Interpolation,
+  /// Bézier handles, used by `Interpolation::Bezier`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub handles: Option<Handles<T>>
+}
+
+impl<T> Key<T> {
+  /// Create a new key.
+  pub fn new(t: Time, value: T, interpolation: Interpolation) -> Self {
+    Key {
+      t: t,
+      value: value,
+      interpolation: interpolation,
+      handles: None
+    }
+  }
+
+  /// Set the Bézier handles of a key.
+  pub fn with_handles(self, in_handle: T, out_handle: T) -> Self {
+    Key {
+      handles: Some(Handles::new(in_handle, out_handle)),
+      .. self
+    }
+  }
+}
+
+/// Bézier handles of a `Key`.
+///
+/// Handles live in the same space as the value of the key. The *in* handle shapes the segment
+/// arriving at the key and the *out* handle shapes the segment leaving it.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Handles<T> {
+  /// Handle used on the segment ending at the key.
+  #[serde(rename = "in")]
+  pub in_handle: T,
+  /// Handle used on the segment starting at the key.
+  #[serde(rename = "out")]
+  pub out_handle: T
+}
+
+impl<T> Handles<T> {
+  pub fn new(in_handle: T, out_handle: T) -> Self {
+    Handles {
+      in_handle: in_handle,
+      out_handle: out_handle
+    }
+  }
+}
+
+/// Interpolation mode.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Interpolation {
+  /// Hold a `Key` until the time passes the normalized step threshold, in which case the next
+  /// key is used.
+  ///
+  /// *Note: if you set the threshold to `0.5`, the first key will be used until the time is half
+  /// between the two keys; the second key will be in used afterwards. If you set it to `1.0`, the
+  /// first key will be kept until the next key.*
+  #[serde(rename = "step")]
+  Step(f32),
+  /// Linear interpolation between a key and the next one.
+  #[serde(rename = "linear")]
+  Linear,
+  /// Cosine interpolation between a key and the next one.
+  #[serde(rename = "cosine")]
+  Cosine,
+  /// Catmull-Rom interpolation.
+  #[serde(rename = "catmull_rom")]
+  CatmullRom,
+  /// Cubic Bézier interpolation between a key and the next one.
+  ///
+  /// The curve is shaped by the *out* handle of the key and the *in* handle of the next one. A key
+  /// without handles uses its own value as handles.
+  #[serde(rename = "bezier")]
+  Bezier,
+  /// Kochanek-Bartels interpolation.
+  ///
+  /// The tangents at a key are controlled by its tension, continuity and bias. The segment between
+  /// a key and the next one uses the parameters of both keys; if the next key doesn’t use
+  /// Kochanek-Bartels interpolation, neutral parameters are used for it. At the boundaries of the
+  /// spline, the missing neighbour is replaced by the key itself, so that – unlike
+  /// `Interpolation::CatmullRom` – every segment can be sampled.
+  #[serde(rename = "kochanek_bartels")]
+  KochanekBartels(TCB),
+  /// Interpolation between a key and the next one following an easing function.
+  #[serde(rename = "ease")]
+  Ease(Ease),
+  /// Linear interpolation between a key and the next one, performed in a color space.
+  ///
+  /// Only colors – `Vector3<f32>` and `Vector4<f32>` as linear RGB(A) – are affected by the color
+  /// space; other types are blended with `Interpolation::Linear`.
+  #[serde(rename = "blend")]
+  Blend(ColorSpace)
+}
+
+impl Default for Interpolation {
+  /// `Interpolation::Linear` is the default.
+  fn default() -> Self {
+    Interpolation::Linear
+  }
+}
+
+/// Tension, continuity and bias parameters of a `Key` using `Interpolation::KochanekBartels`.
+///
+/// All parameters default to `0`, which gives a Catmull-Rom like curve.
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TCB {
+  /// Tension; `1` tightens the curve around the key and `-1` loosens it.
+  #[serde(default)]
+  pub tension: f32,
+  /// Continuity; non-zero values create corners at the key.
+  #[serde(default)]
+  pub continuity: f32,
+  /// Bias; `1` makes the curve overshoot after the key and `-1` before it.
+  #[serde(default)]
+  pub bias: f32
+}
+
+impl TCB {
+  pub fn new(tension: f32, continuity: f32, bias: f32) -> Self {
+    TCB {
+      tension: tension,
+      continuity: continuity,
+      bias: bias
+    }
+  }
+}
+
+/// Extrapolation mode.
+///
+/// Extrapolation is used when sampling a spline before its first key or after its last one.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Extrapolation {
+  /// No extrapolation: sampling outside of the keys doesn’t yield any value.
+  #[serde(rename = "none")]
+  None,
+  /// Hold the value of the first or last key.
+  #[serde(rename = "constant")]
+  Constant,
+  /// Repeat the spline.
+  #[serde(rename = "cycle")]
+  Cycle,
+  /// Repeat the spline, offsetting each repetition by the difference between the values of the last
+  /// and first keys.
+  #[serde(rename = "cycle_offset")]
+  CycleWithOffset,
+  /// Repeat the spline, playing it backwards every other repetition.
+  #[serde(rename = "ping_pong")]
+  PingPong,
+  /// Continue the spline linearly, following its tangent at the first or last key.
+  ///
+  /// Types that cannot be differentiated – see `Differentiate` – continue the first or last segment
+  /// along the line between its two keys instead.
+  #[serde(rename = "linear")]
+  Linear
+}
+
+impl Default for Extrapolation {
+  /// `Extrapolation::None` is the default.
+  fn default() -> Self {
+    Extrapolation::None
+  }
+}
+
+/// Spline curve used to provide interpolation between control points (keys).
+///
+/// A spline loaded with its key times in beats remembers its tempo map, so that it’s saved in beats
+/// as well.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+pub struct Spline<T> {
+  keys: Vec<Key<T>>,
+  #[serde(default)]
+  pre: Extrapolation,
+  #[serde(default)]
+  post: Extrapolation,
+  // name of the tempo map and the tempo map used to convert the key times from beats
+  #[serde(skip_serializing, skip_deserializing)]
+  tempo: Option<(String, TempoMap)>
+}
+
+impl<T> Spline<T> {
+  /// Create a new spline out of keys. The keys don’t have to be sorted because they’re sorted by
+  /// this function.
+  ///
+  /// The spline doesn’t extrapolate; see `Spline::with_extrapolation` to change that.
+  pub fn from_keys(mut keys: Vec<Key<T>>) -> Self {
+    keys.sort_by(|k0, k1| k0.t.partial_cmp(&k1.t).unwrap());
+
+    Spline {
+      keys: keys,
+      pre: Extrapolation::None,
+      post: Extrapolation::None,
+      tempo: None
+    }
+  }
+
+  /// Keys of the spline, sorted by time.
+  pub fn keys(&self) -> &[Key<T>] {
+    &self.keys
+  }
+
+  /// Number of keys in the spline.
+  pub fn len(&self) -> usize {
+    self.keys.len()
+  }
+
+  /// Whether the spline has no key.
+  pub fn is_empty(&self) -> bool {
+    self.keys.is_empty()
+  }
+
+  /// Insert a key, keeping the keys sorted, and return its index.
+  ///
+  /// If keys already exist at the time of the new key, the new key is inserted after them.
+  pub fn insert_key(&mut self, key: Key<T>) -> usize {
+    let i = upper_bound(&self.keys, key.t);
+    self.keys.insert(i, key);
+    i
+  }
+
+  /// Remove the key at the given index.
+  pub fn remove_key(&mut self, i: usize) -> Option<Key<T>> {
+    if i < self.keys.len() {
+      Some(self.keys.remove(i))
+    } else {
+      None
+    }
+  }
+
+  /// Remove the key the nearest to the given time if it’s at most `epsilon` away from it.
+  pub fn remove_key_at(&mut self, t: Time, epsilon: Time) -> Option<Key<T>> {
+    match self.nearest_key(t) {
+      Some(i) if (self.keys[i].t - t).abs() <= epsilon => self.remove_key(i),
+      _ => None
+    }
+  }
+
+  /// Move the key at the given index to a new time and return its new index.
+  pub fn move_key(&mut self, i: usize, t: Time) -> Option<usize> {
+    self.remove_key(i).map(|mut key| {
+      key.t = t;
+      self.insert_key(key)
+    })
+  }
+
+  /// Change the value of the key at the given index and return its previous value.
+  pub fn set_value(&mut self, i: usize, value: T) -> Option<T> {
+    self.keys.get_mut(i).map(|key| replace(&mut key.value, value))
+  }
+
+  /// Change the interpolation mode of the key at the given index and return the previous one.
+  pub fn set_interpolation(&mut self, i: usize, interpolation: Interpolation) -> Option<Interpolation> {
+    self.keys.get_mut(i).map(|key| replace(&mut key.interpolation, interpolation))
+  }
+
+  /// Index of the key the nearest to the given time.
+  pub fn nearest_key(&self, t: Time) -> Option<usize> {
+    let keys = &self.keys;
+    let i = upper_bound(keys, t);
+
+    if keys.is_empty() {
+      None
+    } else if i == 0 {
+      Some(0)
+    } else if i == keys.len() || t - keys[i - 1].t <= keys[i].t - t {
+      Some(i - 1)
+    } else {
+      Some(i)
+    }
+  }
+
+  /// Set the extrapolation modes used before the first key (`pre`) and after the last one (`post`).
+  pub fn with_extrapolation(mut self, pre: Extrapolation, post: Extrapolation) -> Self {
+    self.set_extrapolation(pre, post);
+    self
+  }
+
+  /// Change the extrapolation modes used before the first key (`pre`) and after the last one
+  /// (`post`).
+  pub fn set_extrapolation(&mut self, pre: Extrapolation, post: Extrapolation) {
+    self.pre = pre;
+    self.post = post;
+  }
+
+  /// Extrapolation modes used before the first key and after the last one, respectively.
+  pub fn extrapolation(&self) -> (Extrapolation, Extrapolation) {
+    (self.pre, self.post)
+  }
+
+  /// Name of the tempo map the spline was loaded with, if its key times were in beats.
+  pub fn tempo(&self) -> Option<&str> {
+    self.tempo.as_ref().map(|&(ref name, _)| name.as_str())
+  }
+
+  /// Sample a spline at a given time.
+  ///
+  /// Sampling before the first key or at and after the last one uses the extrapolation modes of the
+  /// spline.
+  ///
+  /// # Return
+  ///
+  /// `None` if you try to sample a value at a time that has no key associated with and that the
+  /// extrapolation mode is `Extrapolation::None`. That can also happen if you try to sample between
+  /// two keys with a specific interpolation mode that make the sampling impossible. For instance,
+  /// `Interpolate::CatmullRom` requires *four* keys. If you’re near the beginning of the spline or
+  /// its end, ensure you have enough keys around to make the sampling.
+  pub fn sample(&self, t: Time) -> Option<T> where T: Interpolate {
+    let keys = &self.keys;
+
+    if keys.is_empty() {
+      return None;
+    }
+
+    if t < keys[0].t {
+      self.extrapolate(t, self.pre)
+    } else if t >= keys[keys.len() - 1].t {
+      self.extrapolate(t, self.post)
+    } else {
+      search_lower_cp(keys, t).and_then(|i| self.sample_segment(i, t))
+    }
+  }
+
+  // Sample the segment starting at the key i.
+  fn sample_segment(&self, i: usize, t: Time) -> Option<T> where T: Interpolate {
+    let keys = &self.keys;
+    let cp0 = &keys[i];
+
+    match cp0.interpolation {
+      Interpolation::Step(threshold) => {
+        let cp1 = &keys[i+1];
+        let nt = normalize_time(t, cp0, cp1);
+        Some(if nt < threshold { cp0.value } else { cp1.value })
+      },
+      Interpolation::Linear => {
+        let cp1 = &keys[i+1];
+        let nt = normalize_time(t, cp0, cp1);
+
+        Some(Interpolate::lerp(cp0.value, cp1.value, nt))
+      },
+      Interpolation::Cosine => {
+        let cp1 = &keys[i+1];
+        let nt = normalize_time(t, cp0, cp1);
+        let cos_nt = (1. - f32::cos(nt * consts::PI)) * 0.5;
+
+        Some(Interpolate::lerp(cp0.value, cp1.value, cos_nt))
+      },
+      Interpolation::CatmullRom => {
+        // We need at least four points for Catmull Rom; ensure we have them, otherwise, return
+        // None.
+        if i == 0 || i >= keys.len() - 2 {
+          None
+        } else {
+          let cp1 = &keys[i+1];
+          let cpm0 = &keys[i-1];
+          let cpm1 = &keys[i+2];
+          let nt = normalize_time(t, cp0, cp1);
+
+          Some(Interpolate::cubic_hermite((cpm0.value, cpm0.t), (cp0.value, cp0.t), (cp1.value, cp1.t), (cpm1.value, cpm1.t), nt))
+        }
+      },
+      Interpolation::Bezier => {
+        let cp1 = &keys[i+1];
+        let nt = normalize_time(t, cp0, cp1);
+        let out_handle = cp0.handles.map_or(cp0.value, |h| h.out_handle);
+        let in_handle = cp1.handles.map_or(cp1.value, |h| h.in_handle);
+
+        Some(Interpolate::cubic_bezier(cp0.value, out_handle, in_handle, cp1.value, nt))
+      },
+      Interpolation::KochanekBartels(tcb0) => {
+        let cp1 = &keys[i+1];
+        let cpm0 = if i == 0 { cp0 } else { &keys[i-1] };
+        let cpm1 = keys.get(i+2).unwrap_or(cp1);
+        let nt = normalize_time(t, cp0, cp1);
+        let tcb1 = match cp1.interpolation {
+          Interpolation::KochanekBartels(tcb) => tcb,
+          _ => TCB::default()
+        };
+
+        Some(Interpolate::kochanek_bartels((cpm0.value, cpm0.t), (cp0.value, cp0.t), (cp1.value, cp1.t), (cpm1.value, cpm1.t), tcb0, tcb1, nt))
+      },
+      Interpolation::Ease(ease) => {
+        let cp1 = &keys[i+1];
+        let nt = normalize_time(t, cp0, cp1);
+
+        Some(Interpolate::lerp(cp0.value, cp1.value, ease.apply(nt)))
+      },
+      Interpolation::Blend(space) => {
+        let cp1 = &keys[i+1];
+        let nt = normalize_time(t, cp0, cp1);
+
+        Some(Interpolate::blend(cp0.value, cp1.value, nt, space))
+      }
+    }
+  }
+
+  // Sample a spline at a time between the first and the last key, clamping to the values of these
+  // keys to absorb rounding errors.
+  fn sample_in_range(&self, t: Time) -> Option<T> where T: Interpolate {
+    let keys = &self.keys;
+    let first = &keys[0];
+    let last = &keys[keys.len() - 1];
+
+    if t <= first.t {
+      Some(first.value)
+    } else if t >= last.t {
+      Some(last.value)
+    } else {
+      search_lower_cp(keys, t).and_then(|i| self.sample_segment(i, t))
+    }
+  }
+
+  // Sample a spline outside of its keys according to an extrapolation mode.
+  fn extrapolate(&self, t: Time, mode: Extrapolation) -> Option<T> where T: Interpolate {
+    let keys = &self.keys;
+    let len = keys.len();
+    let first = &keys[0];
+    let last = &keys[len - 1];
+    let end = if t < first.t { first } else { last };
+    let period = last.t - first.t;
+
+    match mode {
+      Extrapolation::None => None,
+      Extrapolation::Constant => Some(end.value),
+      Extrapolation::Linear => T::extrapolate_linear(self, t),
+      Extrapolation::Cycle | Extrapolation::CycleWithOffset | Extrapolation::PingPong => {
+        if period <= 0. {
+          return Some(end.value);
+        }
+
+        // n is the index of the repetition and frac the normalized time in it
+        let u = (t - first.t) / period;
+        let n = u.floor();
+        let frac = u - n;
+
+        match mode {
+          Extrapolation::Cycle => self.sample_in_range(first.t + frac * period),
+          Extrapolation::CycleWithOffset => {
+            self.sample_in_range(first.t + frac * period).map(|v| cycle_offset(v, first.value, last.value, n))
+          },
+          _ => {
+            let frac = if n % 2. != 0. { 1. - frac } else { frac };
+            self.sample_in_range(first.t + frac * period)
+          }
+        }
+      }
+    }
+  }
+
+  // Linear extrapolation along the line between the two keys of the first or last segment.
+  fn extrapolate_chord(&self, t: Time) -> Option<T> where T: Interpolate {
+    let keys = &self.keys;
+    let len = keys.len();
+
+    if len < 2 {
+      return keys.first().map(|key| key.value);
+    }
+
+    let (cp0, cp1) = if t < keys[0].t { (&keys[0], &keys[1]) } else { (&keys[len - 2], &keys[len - 1]) };
+
+    Some(Interpolate::lerp(cp0.value, cp1.value, normalize_time(t, cp0, cp1)))
+  }
+
+  // Linear extrapolation along the tangent of the spline at its first or last key. Falls back to the
+  // chord if the end segment cannot be differentiated.
+  fn extrapolate_tangent(&self, t: Time) -> Option<T> where T: Differentiate {
+    let keys = &self.keys;
+    let len = keys.len();
+
+    if len < 2 {
+      return keys.first().map(|key| key.value);
+    }
+
+    let end = if t < keys[0].t { &keys[0] } else { &keys[len - 1] };
+
+    match self.end_velocity(t) {
+      Some(velocity) => Some(end.value + velocity * (t - end.t)),
+      None => self.extrapolate_chord(t)
+    }
+  }
+
+  // Velocity of the spline at its first key if t is before it, or at its last key otherwise.
+  fn end_velocity(&self, t: Time) -> Option<T> where T: Differentiate {
+    let keys = &self.keys;
+    let len = keys.len();
+
+    if len < 2 {
+      None
+    } else if t < keys[0].t {
+      self.differentiate_segment(0, keys[0].t, 1)
+    } else {
+      self.differentiate_segment(len - 2, keys[len - 1].t, 1)
+    }
+  }
+
+  /// Sample the velocity of a spline – its first derivative with respect to time – at a given time.
+  ///
+  /// # Return
+  ///
+  /// `None` in the same situations as `Spline::sample`.
+  pub fn sample_velocity(&self, t: Time) -> Option<T> where T: Differentiate {
+    self.differentiate(t, 1)
+  }
+
+  /// Sample the acceleration of a spline – its second derivative with respect to time – at a given
+  /// time.
+  ///
+  /// # Return
+  ///
+  /// `None` in the same situations as `Spline::sample`.
+  pub fn sample_acceleration(&self, t: Time) -> Option<T> where T: Differentiate {
+    self.differentiate(t, 2)
+  }
+
+  // Derivative of order 1 or 2 of the spline at a given time.
+  fn differentiate(&self, t: Time, order: u8) -> Option<T> where T: Differentiate {
+    let keys = &self.keys;
+
+    if keys.is_empty() {
+      return None;
+    }
+
+    if t < keys[0].t {
+      self.extrapolate_derivative(t, self.pre, order)
+    } else if t >= keys[keys.len() - 1].t {
+      self.extrapolate_derivative(t, self.post, order)
+    } else {
+      search_lower_cp(keys, t).and_then(|i| self.differentiate_segment(i, t, order))
+    }
+  }
+
+  // Derivative of order 1 or 2 of the segment starting at the key i.
+  fn differentiate_segment(&self, i: usize, t: Time, order: u8) -> Option<T> where T: Differentiate {
+    let keys = &self.keys;
+    let cp0 = &keys[i];
+    let cp1 = &keys[i+1];
+    let nt = normalize_time(t, cp0, cp1);
+    // derivatives are computed with respect to the normalized time; this rescales them
+    let scale = (cp1.t - cp0.t).powi(order as i32);
+
+    let d = match cp0.interpolation {
+      Interpolation::Step(_) => return Some(T::zero()),
+      Interpolation::Linear => {
+        if order > 1 {
+          return Some(T::zero());
+        }
+
+        cp1.value - cp0.value
+      },
+      Interpolation::Cosine => {
+        let pi = consts::PI;
+        let ds = if order == 1 { pi * 0.5 * f32::sin(nt * pi) } else { pi * pi * 0.5 * f32::cos(nt * pi) };
+
+        (cp1.value - cp0.value) * ds
+      },
+      Interpolation::CatmullRom => {
+        if i == 0 || i >= keys.len() - 2 {
+          return None;
+        }
+
+        // same tangents as in cubic_hermite
+        let cpm0 = &keys[i-1];
+        let cpm1 = &keys[i+2];
+        let m0 = (cp1.value - cpm0.value) / (cp1.t - cpm0.t);
+        let m1 = (cpm1.value - cp0.value) / (cpm1.t - cp0.t);
+
+        hermite_order(cp0.value, m0, cp1.value, m1, nt, order)
+      },
+      Interpolation::Bezier => {
+        let out_handle = cp0.handles.map_or(cp0.value, |h| h.out_handle);
+        let in_handle = cp1.handles.map_or(cp1.value, |h| h.in_handle);
+
+        cubic_bezier_order(cp0.value, out_handle, in_handle, cp1.value, nt, order)
+      },
+      Interpolation::KochanekBartels(tcb0) => {
+        let cpm0 = if i == 0 { cp0 } else { &keys[i-1] };
+        let cpm1 = keys.get(i+2).unwrap_or(cp1);
+        let tcb1 = match cp1.interpolation {
+          Interpolation::KochanekBartels(tcb) => tcb,
+          _ => TCB::default()
+        };
+        let (m0, m1) = kochanek_bartels_tangents((cpm0.value, cpm0.t), (cp0.value, cp0.t), (cp1.value, cp1.t), (cpm1.value, cpm1.t), tcb0, tcb1);
+
+        hermite_order(cp0.value, m0, cp1.value, m1, nt, order)
+      },
+      Interpolation::Ease(ease) => (cp1.value - cp0.value) * ease.derivative(nt, order),
+      Interpolation::Blend(space) => {
+        // no closed form in most color spaces; use finite differences
+        let h = 1e-3;
+        let blend = |x| T::blend(cp0.value, cp1.value, x, space);
+
+        if order == 1 {
+          (blend(nt + h) - blend(nt - h)) / (2. * h)
+        } else {
+          (blend(nt + h) - blend(nt) * 2. + blend(nt - h)) / (h * h)
+        }
+      }
+    };
+
+    Some(d / scale)
+  }
+
+  // Derivative of order 1 or 2 of the spline at a time between the first and the last key. Times
+  // on or past the keys use the derivative at the end of the first or last segment.
+  fn differentiate_in_range(&self, t: Time, order: u8) -> Option<T> where T: Differentiate {
+    let keys = &self.keys;
+    let len = keys.len();
+
+    if len < 2 {
+      Some(T::zero())
+    } else if t <= keys[0].t {
+      self.differentiate_segment(0, keys[0].t, order)
+    } else if t >= keys[len - 1].t {
+      self.differentiate_segment(len - 2, keys[len - 1].t, order)
+    } else {
+      search_lower_cp(keys, t).and_then(|i| self.differentiate_segment(i, t, order))
+    }
+  }
+
+  // Derivative of order 1 or 2 of the spline outside of its keys according to an extrapolation
+  // mode.
+  fn extrapolate_derivative(&self, t: Time, mode: Extrapolation, order: u8) -> Option<T> where T: Differentiate {
+    let keys = &self.keys;
+    let len = keys.len();
+    let first = &keys[0];
+    let last = &keys[len - 1];
+    let period = last.t - first.t;
+
+    match mode {
+      Extrapolation::None => None,
+      Extrapolation::Constant => Some(T::zero()),
+      Extrapolation::Linear => {
+        if len < 2 || order > 1 {
+          return Some(T::zero());
+        }
+
+        self.end_velocity(t).or_else(|| {
+          let (cp0, cp1) = if t < first.t { (&keys[0], &keys[1]) } else { (&keys[len - 2], &keys[len - 1]) };
+          Some((cp1.value - cp0.value) / (cp1.t - cp0.t))
+        })
+      },
+      Extrapolation::Cycle | Extrapolation::CycleWithOffset | Extrapolation::PingPong => {
+        if period <= 0. {
+          return Some(T::zero());
+        }
+
+        let u = (t - first.t) / period;
+        let n = u.floor();
+        let frac = u - n;
+
+        if mode == Extrapolation::PingPong && n % 2. != 0. {
+          // playing backwards flips the sign of the velocity but not the one of the acceleration
+          let d = self.differentiate_in_range(first.t + (1. - frac) * period, order);
+          if order == 1 { d.map(|d| d * -1.) } else { d }
+        } else {
+          self.differentiate_in_range(first.t + frac * period, order)
+        }
+      }
+    }
+  }
+
+  /// Sample a spline at a given time with clamping.
+  ///
+  /// # Return
+  ///
+  /// If you sample before the first key or after the last one,
+  /// return the first key or the last one, respectively.
+  ///
+  /// `None` if the spline has no key, or in the same situations as `Spline::sample` between the
+  /// keys.
+  pub fn clamped_sample(&self, t: Time) -> Option<T> where T: Interpolate {
+    let (first, last) = match (self.keys.first(), self.keys.last()) {
+      (Some(first), Some(last)) => (first, last),
+      _ => return None
+    };
+
+    if t <= first.t {
+      Some(first.value)
+    } else if t >= last.t {
+      Some(last.value)
+    } else {
+      self.sample(t)
+    }
+  }
+}
+
+impl<T> Load for Spline<T> where T: SplineDeserializerAdapter {
+  type Args = ();
+
+  const TY_STR: &'static str = "splines";
+
+  fn load<P>(path: P, cache: &mut ResCache, _: Self::Args) -> Result<Self, LoadError> where P: AsRef<Path> {
+    let path = path.as_ref();
+
+    info!("loading spline: {:?}", path);
+
+    let file = File::open(path).map_err(|e| LoadError::FileNotFound(path.to_path_buf(), format!("{:?}", e)))?;
+    let spline_file: SplineFile<T::Deserialized> = from_reader(file).map_err(|e| LoadError::ParseFailed(format!("{:?}", e)))?;
+
+    spline_from_file(spline_file, cache)
+  }
+}
+
+impl<T> Save for Spline<T> where T: SplineDeserializerAdapter + SplineSerializerAdapter {
+  fn save<P>(&self, path: P) -> Result<(), SaveError> where P: AsRef<Path> {
+    let path = path.as_ref();
+
+    info!("saving spline: {:?}", path);
+
+    // key times are saved in beats if the spline was loaded in beats
+    let manifest = spline_to_manifest(self, self.tempo.as_ref());
+
+    let mut file = File::create(path).map_err(|e| SaveError::FileCreationFailed(path.to_path_buf(), format!("{:?}", e)))?;
+    to_writer_pretty(&mut file, &manifest).map_err(|e| SaveError::SerializationFailed(format!("{:?}", e)))
+  }
+}
+
+// Spline as stored on disk, with its extrapolation modes. If a tempo map is named, the keys’ times
+// are in beats.
+#[derive(Deserialize, Serialize)]
+struct SplineManifest<T> {
+  keys: Vec<Key<T>>,
+  #[serde(default)]
+  pre: Extrapolation,
+  #[serde(default)]
+  post: Extrapolation,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  tempo: Option<String>
+}
+
+// A spline file contains either a whole manifest or only the keys of the spline.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SplineFile<T> {
+  Keys(Vec<Key<T>>),
+  Manifest(SplineManifest<T>)
+}
+
+impl<T> SplineFile<T> {
+  fn into_manifest(self) -> SplineManifest<T> {
+    match self {
+      SplineFile::Keys(keys) => SplineManifest {
+        keys: keys,
+        pre: Extrapolation::default(),
+        post: Extrapolation::default(),
+        tempo: None
+      },
+      SplineFile::Manifest(manifest) => manifest
+    }
+  }
+}
+
+// Turn the deserialized content of a spline file into a spline. Musical times are converted into
+// seconds with the tempo map found in the cache.
+fn spline_from_file<T>(spline_file: SplineFile<T::Deserialized>, cache: &mut ResCache) -> Result<Spline<T>, LoadError> where T: SplineDeserializerAdapter {
+  let mut manifest = spline_file.into_manifest();
+
+  // getting the tempo map from the cache makes the spline reload along with it
+  let tempo = match manifest.tempo.take() {
+    Some(tempo_key) => {
+      let tempo = cache.get::<TempoMap>(&tempo_key, ()).ok_or_else(|| LoadError::ConversionFailed(format!("unknown tempo map {:?}", tempo_key)))?;
+      let tempo = tempo.borrow().clone();
+      Some((tempo_key, tempo))
+    },
+    None => None
+  };
+
+  Ok(spline_from_manifest(manifest, tempo))
+}
+
+// Turn a spline manifest into a spline, converting the key times from beats if a tempo map is
+// given.
+fn spline_from_manifest<T>(manifest: SplineManifest<T::Deserialized>, tempo: Option<(String, TempoMap)>) -> Spline<T> where T: SplineDeserializerAdapter {
+  let mut spline = Spline::from_keys(manifest.keys.into_iter().map(|key| {
+    let handles = key.handles.map(|h| Handles::new(T::from_deserialized(h.in_handle), T::from_deserialized(h.out_handle)));
+    let t = match tempo {
+      Some((_, ref tempo)) => tempo.beats_to_secs(key.t as f64) as Time,
+      None => key.t
+    };
+
+    Key {
+      t: t,
+      value: T::from_deserialized(key.value),
+      interpolation: key.interpolation,
+      handles: handles
+    }
+  }).collect());
+
+  spline.tempo = tempo;
+
+  spline.with_extrapolation(manifest.pre, manifest.post)
+}
+
+// Turn a spline into a spline manifest, converting the key times into beats if a tempo map is
+// given.
+fn spline_to_manifest<T>(spline: &Spline<T>, tempo: Option<&(String, TempoMap)>) -> SplineManifest<T::Serialized> where T: SplineSerializerAdapter {
+  SplineManifest {
+    keys: spline.keys.iter().map(|key| {
+      let handles = key.handles.as_ref().map(|h| Handles::new(h.in_handle.to_serialized(), h.out_handle.to_serialized()));
+      let t = match tempo {
+        Some(&(_, ref tempo)) => tempo.secs_to_beats(key.t as f64) as Time,
+        None => key.t
+      };
+
+      Key {
+        t: t,
+        value: key.value.to_serialized(),
+        interpolation: key.interpolation,
+        handles: handles
+      }
+    }).collect(),
+    pre: spline.pre,
+    post: spline.post,
+    tempo: tempo.map(|&(ref name, _)| name.clone())
+  }
+}
+
+/// Deserialize a spline embedded in another document – with `#[serde(deserialize_with)]` – in the
+/// same format as spline files: either a list of keys or a manifest with extrapolation modes.
+///
+/// Key times are in seconds: an embedded spline cannot name a tempo map.
+pub fn deserialize_spline<D, T>(deserializer: D) -> Result<Spline<T>, D::Error> where D: Deserializer, T: SplineDeserializerAdapter {
+  let manifest = SplineFile::<T::Deserialized>::deserialize(deserializer)?.into_manifest();
+
+  if let Some(ref tempo_key) = manifest.tempo {
+    return Err(D::Error::custom(format!("embedded splines cannot use the tempo map {:?}", tempo_key)));
+  }
+
+  Ok(spline_from_manifest(manifest, None))
+}
+
+/// Serialize a spline embedded in another document – with `#[serde(serialize_with)]` – in the same
+/// format as spline files. Key times are written in seconds.
+pub fn serialize_spline<S, T>(spline: &Spline<T>, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer, T: SplineSerializerAdapter {
+  spline_to_manifest(spline, None).serialize(serializer)
+}
+
+/// Spline deserializer adapter used to deserialize splines which keys’ values types don’t directly
+/// implement deserialization.
+pub trait SplineDeserializerAdapter {
+  type Deserialized: Deserialize;
+
+  fn from_deserialized(de: Self::Deserialized) -> Self;
+}
+
+impl SplineDeserializerAdapter for f32 {
+  type Deserialized = Self;
+
+  fn from_deserialized(de: Self::Deserialized) -> Self {
+    de
+  }
+}
+
+impl<T> SplineDeserializerAdapter for Vector2<T> where T: Clone + Deserialize {
+  type Deserialized = [T; 2];
+
+  fn from_deserialized(de: Self::Deserialized) -> Self {
+    (&de).into()
+  }
+}
+
+impl<T> SplineDeserializerAdapter for Vector3<T> where T: Clone + Deserialize {
+  type Deserialized = [T; 3];
+
+  fn from_deserialized(de: Self::Deserialized) -> Self {
+    (&de).into()
+  }
+}
+
+impl<T> SplineDeserializerAdapter for Vector4<T> where T: Clone + Deserialize {
+  type Deserialized = [T; 4];
+
+  fn from_deserialized(de: Self::Deserialized) -> Self {
+    (&de).into()
+  }
+}
+
+impl<T> SplineDeserializerAdapter for UnitQuaternion<T> where T: BaseFloat + Deserialize {
+  type Deserialized = [T; 4];
+
+  fn from_deserialized(de: Self::Deserialized) -> Self {
+    Unit::new((&de).into())
+  }
+}
+
+impl SplineDeserializerAdapter for Scale {
+  type Deserialized = [f32; 3];
+
+  fn from_deserialized(de: Self::Deserialized) -> Self {
+    Scale::from(&de)
+  }
+}
+
+/// Spline serializer adapter used to serialize splines which keys’ values types don’t directly
+/// implement serialization. It’s the dual of `SplineDeserializerAdapter`.
+pub trait SplineSerializerAdapter {
+  type Serialized: Serialize;
+
+  fn to_serialized(&self) -> Self::Serialized;
+}
+
+impl SplineSerializerAdapter for f32 {
+  type Serialized = Self;
+
+  fn to_serialized(&self) -> Self::Serialized {
+    *self
+  }
+}
+
+impl<T> SplineSerializerAdapter for Vector2<T> where T: Clone + Serialize {
+  type Serialized = [T; 2];
+
+  fn to_serialized(&self) -> Self::Serialized {
+    [self.x.clone(), self.y.clone()]
+  }
+}
+
+impl<T> SplineSerializerAdapter for Vector3<T> where T: Clone + Serialize {
+  type Serialized = [T; 3];
+
+  fn to_serialized(&self) -> Self::Serialized {
+    [self.x.clone(), self.y.clone(), self.z.clone()]
+  }
+}
+
+impl<T> SplineSerializerAdapter for Vector4<T> where T: Clone + Serialize {
+  type Serialized = [T; 4];
+
+  fn to_serialized(&self) -> Self::Serialized {
+    [self.x.clone(), self.y.clone(), self.z.clone(), self.w.clone()]
+  }
+}
+
+impl<T> SplineSerializerAdapter for UnitQuaternion<T> where T: BaseFloat + Serialize {
+  type Serialized = [T; 4];
+
+  fn to_serialized(&self) -> Self::Serialized {
+    let q = self.quaternion();
+    [q.w, q.i, q.j, q.k]
+  }
+}
+
+impl SplineSerializerAdapter for Scale {
+  type Serialized = [f32; 3];
+
+  fn to_serialized(&self) -> Self::Serialized {
+    self.into()
+  }
+}
+
+/// Cursor used to sample a spline during playback.
+///
+/// The cursor remembers the last sampled segment of the spline. Sampling in the same segment or in
+/// the next one – which is what happens when time flows forward – costs *O(1)*; other times fall back
+/// to a binary search. The cursor doesn’t borrow the spline, so that it can live next to it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SplineCursor {
+  segment: usize
+}
+
+impl SplineCursor {
+  pub fn new() -> Self {
+    SplineCursor {
+      segment: 0
+    }
+  }
+
+  /// Sample a spline at a given time. The result is the same as `Spline::sample`.
+  pub fn sample<T>(&mut self, spline: &Spline<T>, t: Time) -> Option<T> where T: Interpolate {
+    let keys = &spline.keys;
+    let len = keys.len();
+
+    if len < 2 || t < keys[0].t || t >= keys[len - 1].t {
+      return spline.sample(t);
+    }
+
+    self.locate(keys, t).and_then(|i| spline.sample_segment(i, t))
+  }
+
+  // Find the lower control point corresponding to a given time, trying the cached segment and the
+  // next one first.
+  fn locate<T>(&mut self, keys: &[Key<T>], t: Time) -> Option<usize> {
+    let i = self.segment;
+    let len = keys.len();
+
+    if i + 1 < len && keys[i].t <= t && t < keys[i+1].t {
+      return Some(i);
+    }
+
+    if i + 2 < len && keys[i+1].t <= t && t < keys[i+2].t {
+      self.segment = i + 1;
+      return Some(i + 1);
+    }
+
+    let found = search_lower_cp(keys, t);
+
+    if let Some(j) = found {
+      self.segment = j;
+    }
+
+    found
+  }
+}
+
+/// Iterator over spline keys.
+pub struct SplineIterator<'a, T> where T: 'a {
+  anim_param: &'a Spline<T>,
+  i: usize
+}
+
+impl<'a, T> Iterator for SplineIterator<'a, T> {
+  type Item = &'a Key<T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let r = self.anim_param.keys.get(self.i);
+
+    if let Some(_) = r {
+      self.i += 1;
+    }
+
+    r
+  }
+}
+
+impl<'a, T> IntoIterator for &'a Spline<T> {
+  type Item = &'a Key<T>;
+  type IntoIter = SplineIterator<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    SplineIterator {
+      anim_param: self,
+      i: 0
+    }
+  }
+}
+
+/// Keys that can be interpolated between. Implementing this trait is required to perform sampling
+/// on splines.
+pub trait Interpolate: Copy {
+  /// Linear interpolation.
+  fn lerp(a: Self, b: Self, t: Time) -> Self;
+  /// Cubic hermite interpolation.
+  ///
+  /// Default to `Self::lerp`.
+  fn cubic_hermite(_: (Self, Time), a: (Self, Time), b: (Self, Time), _: (Self, Time), t: Time) -> Self {
+    Self::lerp(a.0, b.0, t)
+  }
+  /// Cubic Bézier interpolation between `a` and `b`, using `u` and `v` as control points.
+  ///
+  /// Default to De Casteljau’s algorithm, built out of `Self::lerp`.
+  fn cubic_bezier(a: Self, u: Self, v: Self, b: Self, t: Time) -> Self {
+    let au = Self::lerp(a, u, t);
+    let uv = Self::lerp(u, v, t);
+    let vb = Self::lerp(v, b, t);
+    let auv = Self::lerp(au, uv, t);
+    let uvb = Self::lerp(uv, vb, t);
+
+    Self::lerp(auv, uvb, t)
+  }
+  /// Kochanek-Bartels interpolation.
+  ///
+  /// `tcb0` and `tcb1` are the parameters of `a` and `b`, respectively. Default to `Self::lerp`.
+  fn kochanek_bartels(_: (Self, Time), a: (Self, Time), b: (Self, Time), _: (Self, Time), _: TCB, _: TCB, t: Time) -> Self {
+    Self::lerp(a.0, b.0, t)
+  }
+  /// Linear interpolation in a color space.
+  ///
+  /// Default to `Self::lerp`; only colors are concerned by color spaces.
+  fn blend(a: Self, b: Self, t: Time, _: ColorSpace) -> Self {
+    Self::lerp(a, b, t)
+  }
+  /// Sample a spline before its first key or after its last one with `Extrapolation::Linear`.
+  ///
+  /// Default to continuing the end segment along the line between its two keys; types implementing
+  /// `Differentiate` follow the tangent at the end key instead.
+  fn extrapolate_linear(spline: &Spline<Self>, t: Time) -> Option<Self> {
+    spline.extrapolate_chord(t)
+  }
+}
+
+impl Interpolate for f32 {
+  fn lerp(a: Self, b: Self, t: Time) -> Self {
+    lerp(a, b, t)
+  }
+
+  fn extrapolate_linear(spline: &Spline<Self>, t: Time) -> Option<Self> {
+    spline.extrapolate_tangent(t)
+  }
+
+  fn cubic_hermite(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), t: Time) -> Self {
+    cubic_hermite(x, a, b, y, t)
+  }
+
+  fn kochanek_bartels(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), tcb0: TCB, tcb1: TCB, t: Time) -> Self {
+    kochanek_bartels(x, a, b, y, tcb0, tcb1, t)
+  }
+}
+
+impl Interpolate for Vector2<f32> {
+  fn lerp(a: Self, b: Self, t: Time) -> Self {
+    lerp(a, b, t)
+  }
+
+  fn extrapolate_linear(spline: &Spline<Self>, t: Time) -> Option<Self> {
+    spline.extrapolate_tangent(t)
+  }
+
+  fn cubic_hermite(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), t: Time) -> Self {
+    cubic_hermite(x, a, b, y, t)
+  }
+
+  fn kochanek_bartels(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), tcb0: TCB, tcb1: TCB, t: Time) -> Self {
+    kochanek_bartels(x, a, b, y, tcb0, tcb1, t)
+  }
+}
+
+impl Interpolate for Vector3<f32> {
+  fn lerp(a: Self, b: Self, t: Time) -> Self {
+    lerp(a, b, t)
+  }
+
+  fn extrapolate_linear(spline: &Spline<Self>, t: Time) -> Option<Self> {
+    spline.extrapolate_tangent(t)
+  }
+
+  fn cubic_hermite(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), t: Time) -> Self {
+    cubic_hermite(x, a, b, y, t)
+  }
+
+  fn kochanek_bartels(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), tcb0: TCB, tcb1: TCB, t: Time) -> Self {
+    kochanek_bartels(x, a, b, y, tcb0, tcb1, t)
+  }
+
+  fn blend(a: Self, b: Self, t: Time, space: ColorSpace) -> Self {
+    blend_rgb(a, b, t, space)
+  }
+}
+
+impl Interpolate for Vector4<f32> {
+  fn lerp(a: Self, b: Self, t: Time) -> Self {
+    lerp(a, b, t)
+  }
+
+  fn extrapolate_linear(spline: &Spline<Self>, t: Time) -> Option<Self> {
+    spline.extrapolate_tangent(t)
+  }
+
+  fn cubic_hermite(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), t: Time) -> Self {
+    cubic_hermite(x, a, b, y, t)
+  }
+
+  fn kochanek_bartels(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), tcb0: TCB, tcb1: TCB, t: Time) -> Self {
+    kochanek_bartels(x, a, b, y, tcb0, tcb1, t)
+  }
+
+  fn blend(a: Self, b: Self, t: Time, space: ColorSpace) -> Self {
+    blend_rgba(a, b, t, space)
+  }
+}
+
+impl Interpolate for UnitQuaternion<f32> {
+  /// Spherical linear interpolation, taking the shortest path between `a` and `b`.
+  fn lerp(a: Self, b: Self, t: Time) -> Self {
+    slerp(a, closest_quaternion(a, b), t)
+  }
+
+  /// Spherical cubic interpolation (squad).
+  fn cubic_hermite(x: (Self, Time), a: (Self, Time), b: (Self, Time), y: (Self, Time), t: Time) -> Self {
+    squad(x.0, a.0, b.0, y.0, t)
+  }
+}
+
+// Spherical linear interpolation between a and b, without checking for the shortest path.
+fn slerp(a: UnitQuaternion<f32>, b: UnitQuaternion<f32>, t: Time) -> UnitQuaternion<f32> {
+  a * UnitQuaternion::new(&(UnitQuaternion::new(&a.quaternion().conjugate()) * b).quaternion().powf(t))
+}
+
+// Either b or -b – which represent the same rotation –, whichever is the closest to a.
+fn closest_quaternion(a: UnitQuaternion<f32>, b: UnitQuaternion<f32>) -> UnitQuaternion<f32> {
+  let qa = a.quaternion();
+  let qb = b.quaternion();
+
+  if qa.w * qb.w + qa.i * qb.i + qa.j * qb.j + qa.k * qb.k < 0. {
+    UnitQuaternion::new(&Quaternion::new(-qb.w, -qb.i, -qb.j, -qb.k))
+  } else {
+    b
+  }
+}
+
+// Spherical cubic interpolation between a and b, x and y being their outer neighbours.
+fn squad(x: UnitQuaternion<f32>, a: UnitQuaternion<f32>, b: UnitQuaternion<f32>, y: UnitQuaternion<f32>, t: Time) -> UnitQuaternion<f32> {
+  // flip the signs of the keys so that consecutive keys take the shortest path
+  let x = closest_quaternion(a, x);
+  let b = closest_quaternion(a, b);
+  let y = closest_quaternion(b, y);
+
+  let sa = squad_control_point(x, a, b);
+  let sb = squad_control_point(a, b, y);
+
+  slerp(slerp(a, b, t), slerp(sa, sb, t), 2. * t * (1. - t))
+}
+
+// Intermediate squad control point of q, p and n being the previous and next keys.
+fn squad_control_point(p: UnitQuaternion<f32>, q: UnitQuaternion<f32>, n: UnitQuaternion<f32>) -> UnitQuaternion<f32> {
+  let q_inv = UnitQuaternion::new(&q.quaternion().conjugate());
+  let v = (quaternion_log(q_inv * n) + quaternion_log(q_inv * p)) * -0.25;
+
+  q * quaternion_exp(v)
+}
+
+// Logarithm of a unit quaternion, which is a pure quaternion represented by its vector part.
+fn quaternion_log(q: UnitQuaternion<f32>) -> Vector3<f32> {
+  let q = q.quaternion();
+  let v = Vector3::new(q.i, q.j, q.k);
+  let vn = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+
+  if vn < 1e-6 {
+    Vector3::new(0., 0., 0.)
+  } else {
+    v * (vn.atan2(q.w) / vn)
+  }
+}
+
+// Exponential of a pure quaternion represented by its vector part.
+fn quaternion_exp(v: Vector3<f32>) -> UnitQuaternion<f32> {
+  let vn = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+
+  if vn < 1e-6 {
+    UnitQuaternion::new(&Quaternion::new(1., 0., 0., 0.))
+  } else {
+    let s = vn.sin() / vn;
+    UnitQuaternion::new(&Quaternion::new(vn.cos(), v.x * s, v.y * s, v.z * s))
+  }
+}
+
+impl Interpolate for Scale {
+  fn lerp(a: Self, b: Self, t: Time) -> Self {
+    let av = Vector3::new(a.x, a.y, a.z);
+    let bv = Vector3::new(b.x, b.y, b.z);
+    let r = *lerp(av, bv, t).as_ref();
+
+    Scale::from(&r)
+  }
+}
+
+/// Keys that can be differentiated. Implementing this trait is required to sample velocity and
+/// acceleration on splines.
+pub trait Differentiate: Interpolate + Add<Output = Self> + Sub<Output = Self> + Mul<Time, Output = Self> + Div<Time, Output = Self> {
+  /// Null value, derivative of a constant.
+  fn zero() -> Self;
+}
+
+impl Differentiate for f32 {
+  fn zero() -> Self {
+    0.
+  }
+}
+
+impl Differentiate for Vector2<f32> {
+  fn zero() -> Self {
+    Vector2::new(0., 0.)
+  }
+}
+
+impl Differentiate for Vector3<f32> {
+  fn zero() -> Self {
+    Vector3::new(0., 0., 0.)
+  }
+}
+
+impl Differentiate for Vector4<f32> {
+  fn zero() -> Self {
+    Vector4::new(0., 0., 0., 0.)
+  }
+}
+
+// Default implementation of Interpolate::lerp.
+pub fn lerp<T>(a: T, b: T, t: Time) -> T where T: Add<Output = T> + Mul<Time, Output = T> {
+  a * (1. - t) + b * t
+}
+
+// Default implementation of Interpolate::cubic_hermit.
+pub fn cubic_hermite<T>(x: (T, Time), a: (T, Time), b: (T, Time), y: (T, Time), t: Time) -> T
+    where T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Time, Output = T> + Div<Time, Output = T> {
+  // time stuff
+  let t2 = t * t;
+  let t3 = t2 * t;
+  let two_t3 = 2. * t3;
+  let three_t2 = 3. * t2;
+
+  // tangents
+  let m0 = (b.0 - x.0) / (b.1 - x.1);
+	let m1 = (y.0 - a.0) / (y.1 - a.1);
+
+  a.0 * (two_t3 - three_t2 + 1.) + m0 * (t3 - 2. * t2 + t) + b.0 * (-two_t3 + three_t2) + m1 * (t3 - t2)
+}
+
+// Default implementation of Interpolate::kochanek_bartels.
+pub fn kochanek_bartels<T>(x: (T, Time), a: (T, Time), b: (T, Time), y: (T, Time), tcb0: TCB, tcb1: TCB, t: Time) -> T
+    where T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Time, Output = T> + Div<Time, Output = T> {
+  let (m0, m1) = kochanek_bartels_tangents(x, a, b, y, tcb0, tcb1);
+  hermite(a.0, m0, b.0, m1, t)
+}
+
+// Outgoing tangent of a and incoming tangent of b, scaled to the [a; b] segment.
+//
+// A neighbour sharing the time of the key it surrounds is considered missing; the slope of the
+// [a; b] segment is used in place of its slope.
+pub fn kochanek_bartels_tangents<T>(x: (T, Time), a: (T, Time), b: (T, Time), y: (T, Time), tcb0: TCB, tcb1: TCB) -> (T, T)
+    where T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Time, Output = T> + Div<Time, Output = T> {
+  let dt = b.1 - a.1;
+
+  // slopes of the segments before, on and after [a; b]
+  let d = (b.0 - a.0) / dt;
+  let d_prev = if a.1 > x.1 { (a.0 - x.0) / (a.1 - x.1) } else { d };
+  let d_next = if y.1 > b.1 { (y.0 - b.0) / (y.1 - b.1) } else { d };
+
+  let (t0, c0, b0) = (tcb0.tension, tcb0.continuity, tcb0.bias);
+  let (t1, c1, b1) = (tcb1.tension, tcb1.continuity, tcb1.bias);
+
+  let m0 = d_prev * ((1. - t0) * (1. + c0) * (1. + b0) * 0.5) + d * ((1. - t0) * (1. - c0) * (1. - b0) * 0.5);
+  let m1 = d * ((1. - t1) * (1. - c1) * (1. + b1) * 0.5) + d_next * ((1. - t1) * (1. + c1) * (1. - b1) * 0.5);
+
+  (m0 * dt, m1 * dt)
+}
+
+// Cubic Hermite polynomial between a and b with tangents m0 and m1, in normalized time.
+fn hermite<T>(a: T, m0: T, b: T, m1: T, t: Time) -> T where T: Add<Output = T> + Mul<Time, Output = T> {
+  hermite_order(a, m0, b, m1, t, 0)
+}
+
+// Cubic Hermite polynomial – or its derivative of order 1 or 2 – in normalized time.
+fn hermite_order<T>(a: T, m0: T, b: T, m1: T, t: Time, order: u8) -> T where T: Add<Output = T> + Mul<Time, Output = T> {
+  let t2 = t * t;
+  let t3 = t2 * t;
+
+  let h = match order {
+    0 => [2. * t3 - 3. * t2 + 1., t3 - 2. * t2 + t, -2. * t3 + 3. * t2, t3 - t2],
+    1 => [6. * t2 - 6. * t, 3. * t2 - 4. * t + 1., -6. * t2 + 6. * t, 3. * t2 - 2. * t],
+    _ => [12. * t - 6., 6. * t - 4., -12. * t + 6., 6. * t - 2.]
+  };
+
+  a * h[0] + m0 * h[1] + b * h[2] + m1 * h[3]
+}
+
+// Cubic Bézier curve – or its derivative of order 1 or 2 – in normalized time.
+fn cubic_bezier_order<T>(a: T, u: T, v: T, b: T, t: Time, order: u8) -> T where T: Add<Output = T> + Mul<Time, Output = T> {
+  let it = 1. - t;
+
+  let w = match order {
+    0 => [it * it * it, 3. * t * it * it, 3. * t * t * it, t * t * t],
+    1 => [-3. * it * it, 3. * it * it - 6. * t * it, 6. * t * it - 3. * t * t, 3. * t * t],
+    _ => [6. * it, 18. * t - 12., 6. - 18. * t, 6. * t]
+  };
+
+  a * w[0] + u * w[1] + v * w[2] + b * w[3]
+}
+
+// Offset a value by n times the difference between the last and first values of a spline.
+//
+// That is the affine combination v + n * (last - first), expressed with lerps only so that it works
+// with any interpolable type.
+fn cycle_offset<T>(v: T, first: T, last: T, n: f32) -> T where T: Interpolate {
+  let p = T::lerp(first, last, n);
+  T::lerp(first, T::lerp(p, v, 0.5), 2.)
+}
+
+// Normalize a time ([0;1]) given two control points.
+pub fn normalize_time<T>(t: Time, cp: &Key<T>, cp1: &Key<T>) -> Time {
+  (t - cp.t) / (cp1.t - cp.t)
+}
+
+// Index of the first key strictly after a given time.
+fn upper_bound<T>(keys: &[Key<T>], t: Time) -> usize {
+  let mut lo = 0;
+  let mut hi = keys.len();
+
+  while lo < hi {
+    let mid = (lo + hi) / 2;
+
+    if keys[mid].t <= t {
+      lo = mid + 1;
+    } else {
+      hi = mid;
+    }
+  }
+
+  lo
+}
+
+// Find the lower control point corresponding to a given time.
+//
+// That is the last key at or before the given time, provided a key exists after the given time.
+fn search_lower_cp<T>(cps: &[Key<T>], t: Time) -> Option<usize> {
+  let i = upper_bound(cps, t);
+
+  if i == 0 || i >= cps.len() {
+    None
+  } else {
+    Some(i - 1)
+  }
+}
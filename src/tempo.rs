@@ -0,0 +1,329 @@
+//! Musical time.
+//!
+//! Demos are synchronized with music, which is structured in beats and bars rather than seconds. A
+//! `TempoMap` gives the tempo and the time signature of a soundtrack – possibly changing over time –
+//! and converts between seconds, beats and bars.
+//!
+//! On disk, a tempo map gives its initial tempo and signature and a list of changes located in
+//! beats:
+//!
+//! ```json
+//! {
+//!   "bpm": 120,
+//!   "signature": [4, 4],
+//!   "changes": [
+//!     { "beat": 64, "bpm": 140 },
+//!     { "beat": 128, "signature": [3, 4] }
+//!   ]
+//! }
+//! ```
+
+use serde_json::from_reader;
+use std::fs::File;
+use std::path::Path;
+
+use resource::{Load, LoadError, ResCache, Result};
+
+/// Musical time, in beats.
+pub type Beats = f64;
+
+/// Musical time, in bars.
+pub type Bars = f64;
+
+/// Time in seconds.
+pub type Secs = f64;
+
+/// Time signature: number of beats per bar and note value of a beat.
+///
+/// Tempi are expressed in beats per minute, a beat being a unit of the numerator of the signature.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TimeSignature(pub u32, pub u32);
+
+impl Default for TimeSignature {
+  /// Common time (4/4) is the default.
+  fn default() -> Self {
+    TimeSignature(4, 4)
+  }
+}
+
+/// Change of tempo and/or time signature at a given beat.
+///
+/// Signature changes should happen on bar boundaries.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TempoChange {
+  pub beat: Beats,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub bpm: Option<f64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub signature: Option<TimeSignature>
+}
+
+/// Tempo map, used to convert between seconds, beats and bars.
+///
+/// Bars and beats are counted from `0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TempoMap {
+  // sorted segments of constant tempo and signature; the first one starts at beat 0
+  segments: Vec<Segment>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Segment {
+  beat: Beats,
+  secs: Secs,
+  bar: Bars,
+  bpm: f64,
+  signature: TimeSignature
+}
+
+impl TempoMap {
+  /// Create a tempo map with a constant tempo and signature.
+  pub fn new(bpm: f64, signature: TimeSignature) -> Self {
+    Self::with_changes(bpm, signature, &[])
+  }
+
+  /// Create a tempo map with an initial tempo and signature and a list of changes. The changes don’t
+  /// have to be sorted.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a tempo isn’t strictly positive or a signature has no beat per bar.
+  pub fn with_changes(bpm: f64, signature: TimeSignature, changes: &[TempoChange]) -> Self {
+    assert!(bpm > 0. && signature.0 > 0);
+    assert!(changes.iter().all(|change| change.bpm.map_or(true, |bpm| bpm > 0.) && change.signature.map_or(true, |signature| signature.0 > 0)));
+
+    let mut changes = changes.to_vec();
+    changes.sort_by(|a, b| a.beat.partial_cmp(&b.beat).unwrap());
+
+    let mut segments = vec![Segment {
+      beat: 0.,
+      secs: 0.,
+      bar: 0.,
+      bpm: bpm,
+      signature: signature
+    }];
+
+    for change in &changes {
+      let prev = segments[segments.len() - 1];
+      let segment = Segment {
+        beat: change.beat,
+        secs: prev.secs + (change.beat - prev.beat) * 60. / prev.bpm,
+        bar: prev.bar + (change.beat - prev.beat) / prev.signature.0 as f64,
+        bpm: change.bpm.unwrap_or(prev.bpm),
+        signature: change.signature.unwrap_or(prev.signature)
+      };
+
+      if change.beat <= prev.beat {
+        // a change at the same beat as the previous one overrides it
+        let last = segments.len() - 1;
+        segments[last] = Segment { beat: prev.beat, secs: prev.secs, bar: prev.bar, .. segment };
+      } else {
+        segments.push(segment);
+      }
+    }
+
+    TempoMap {
+      segments: segments
+    }
+  }
+
+  /// Tempo, in beats per minute, at a given time.
+  pub fn bpm(&self, secs: Secs) -> f64 {
+    self.segment_by(secs, |s| s.secs).bpm
+  }
+
+  /// Time signature at a given time.
+  pub fn signature(&self, secs: Secs) -> TimeSignature {
+    self.segment_by(secs, |s| s.secs).signature
+  }
+
+  /// Convert seconds into beats.
+  pub fn secs_to_beats(&self, secs: Secs) -> Beats {
+    let s = self.segment_by(secs, |s| s.secs);
+    s.beat + (secs - s.secs) * s.bpm / 60.
+  }
+
+  /// Convert beats into seconds.
+  pub fn beats_to_secs(&self, beats: Beats) -> Secs {
+    let s = self.segment_by(beats, |s| s.beat);
+    s.secs + (beats - s.beat) * 60. / s.bpm
+  }
+
+  /// Convert beats into bars.
+  pub fn beats_to_bars(&self, beats: Beats) -> Bars {
+    let s = self.segment_by(beats, |s| s.beat);
+    s.bar + (beats - s.beat) / s.signature.0 as f64
+  }
+
+  /// Convert bars into beats.
+  pub fn bars_to_beats(&self, bars: Bars) -> Beats {
+    let s = self.segment_by(bars, |s| s.bar);
+    s.beat + (bars - s.bar) * s.signature.0 as f64
+  }
+
+  /// Convert seconds into bars.
+  pub fn secs_to_bars(&self, secs: Secs) -> Bars {
+    self.beats_to_bars(self.secs_to_beats(secs))
+  }
+
+  /// Convert bars into seconds.
+  pub fn bars_to_secs(&self, bars: Bars) -> Secs {
+    self.beats_to_secs(self.bars_to_beats(bars))
+  }
+
+  /// Bar and beat in the bar at a given time.
+  pub fn bar_beat(&self, secs: Secs) -> (u32, Beats) {
+    let beats = self.secs_to_beats(secs);
+    let s = self.segment_by(beats, |s| s.beat);
+    let bars_in_segment = ((beats - s.beat) / s.signature.0 as f64).floor();
+    let bar = s.bar + bars_in_segment;
+
+    (bar as u32, beats - s.beat - bars_in_segment * s.signature.0 as f64)
+  }
+
+  // Last segment starting at or before the given position, positions being given by the projection.
+  fn segment_by<F>(&self, x: f64, position: F) -> &Segment where F: Fn(&Segment) -> f64 {
+    self.segments.iter().rev().find(|s| position(s) <= x).unwrap_or(&self.segments[0])
+  }
+}
+
+impl Load for TempoMap {
+  type Args = ();
+
+  const TY_STR: &'static str = "tempo";
+
+  fn load<P>(path: P, _: &mut ResCache, _: Self::Args) -> Result<Self> where P: AsRef<Path> {
+    let path = path.as_ref();
+
+    info!("loading tempo map: {:?}", path);
+
+    let file = File::open(path).map_err(|e| LoadError::FileNotFound(path.to_path_buf(), format!("{:?}", e)))?;
+    let manifest: TempoMapManifest = from_reader(file).map_err(|e| LoadError::ParseFailed(format!("{:?}", e)))?;
+
+    // invalid tempi and signatures would yield infinite times
+    check_tempo(manifest.bpm, Some(manifest.signature))?;
+
+    for change in &manifest.changes {
+      check_tempo(change.bpm.unwrap_or(1.), change.signature)?;
+    }
+
+    Ok(TempoMap::with_changes(manifest.bpm, manifest.signature, &manifest.changes))
+  }
+}
+
+// Check that a tempo is strictly positive and that a signature has at least one beat per bar.
+fn check_tempo(bpm: f64, signature: Option<TimeSignature>) -> Result<()> {
+  if !(bpm > 0.) {
+    return Err(LoadError::ParseFailed(format!("invalid tempo: {} BPM", bpm)));
+  }
+
+  match signature {
+    Some(TimeSignature(0, unit)) => Err(LoadError::ParseFailed(format!("invalid time signature: 0/{}", unit))),
+    _ => Ok(())
+  }
+}
+
+#[derive(Deserialize)]
+struct TempoMapManifest {
+  bpm: f64,
+  #[serde(default)]
+  signature: TimeSignature,
+  #[serde(default)]
+  changes: Vec<TempoChange>
+}
+
+/// Time expressed either in seconds or in musical time.
+///
+/// In manifests, seconds are given as plain numbers and musical time as `{ "beats": 12 }` or
+/// `{ "bars": 3 }`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TimeSpec {
+  Secs(Secs),
+  Beats { beats: Beats },
+  Bars { bars: Bars }
+}
+
+impl TimeSpec {
+  /// Unit the time is expressed in.
+  pub fn unit(&self) -> TimeUnit {
+    match *self {
+      TimeSpec::Secs(_) => TimeUnit::Secs,
+      TimeSpec::Beats { .. } => TimeUnit::Beats,
+      TimeSpec::Bars { .. } => TimeUnit::Bars
+    }
+  }
+
+  /// Time in seconds, if not expressed in musical time.
+  pub fn secs(&self) -> Option<Secs> {
+    match *self {
+      TimeSpec::Secs(secs) => Some(secs),
+      _ => None
+    }
+  }
+
+  /// Convert to seconds with a tempo map.
+  pub fn to_secs(&self, tempo: &TempoMap) -> Secs {
+    match *self {
+      TimeSpec::Secs(secs) => secs,
+      TimeSpec::Beats { beats } => tempo.beats_to_secs(beats),
+      TimeSpec::Bars { bars } => tempo.bars_to_secs(bars)
+    }
+  }
+
+  /// Convert to a duration in seconds with a tempo map, musical durations starting at `start`.
+  pub fn dur_to_secs(&self, start: Secs, tempo: &TempoMap) -> Secs {
+    match *self {
+      TimeSpec::Secs(secs) => secs,
+      TimeSpec::Beats { beats } => tempo.beats_to_secs(tempo.secs_to_beats(start) + beats) - start,
+      TimeSpec::Bars { bars } => tempo.bars_to_secs(tempo.secs_to_bars(start) + bars) - start
+    }
+  }
+
+  /// Replace musical time by seconds with a tempo map.
+  pub fn resolve(&mut self, tempo: &TempoMap) {
+    *self = TimeSpec::Secs(self.to_secs(tempo));
+  }
+}
+
+impl From<Secs> for TimeSpec {
+  fn from(secs: Secs) -> Self {
+    TimeSpec::Secs(secs)
+  }
+}
+
+/// Unit of a `TimeSpec`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeUnit {
+  Secs,
+  Beats,
+  Bars
+}
+
+impl TimeUnit {
+  /// Express a time in seconds in this unit with a tempo map.
+  pub fn to_spec(&self, secs: Secs, tempo: &TempoMap) -> TimeSpec {
+    match *self {
+      TimeUnit::Secs => TimeSpec::Secs(secs),
+      TimeUnit::Beats => TimeSpec::Beats { beats: tempo.secs_to_beats(secs) },
+      TimeUnit::Bars => TimeSpec::Bars { bars: tempo.secs_to_bars(secs) }
+    }
+  }
+
+  /// Express a duration in seconds in this unit with a tempo map, musical durations starting at
+  /// `start`. This is the inverse of `TimeSpec::dur_to_secs`.
+  pub fn dur_to_spec(&self, start: Secs, dur: Secs, tempo: &TempoMap) -> TimeSpec {
+    match *self {
+      TimeUnit::Secs => TimeSpec::Secs(dur),
+      TimeUnit::Beats => TimeSpec::Beats { beats: tempo.secs_to_beats(start + dur) - tempo.secs_to_beats(start) },
+      TimeUnit::Bars => TimeSpec::Bars { bars: tempo.secs_to_bars(start + dur) - tempo.secs_to_bars(start) }
+    }
+  }
+}
+
+impl Default for TimeUnit {
+  /// Seconds are the default.
+  fn default() -> Self {
+    TimeUnit::Secs
+  }
+}
@@ -1,14 +1,17 @@
 pub use luminance::pixel::{Depth32F, R32F, RGBA32F};
-pub use luminance::texture::{Dim2, Flat, MagFilter, MinFilter, Sampler, Texture, Unit, Wrap};
+pub use luminance::texture::{Dim1, Dim2, Flat, MagFilter, MinFilter, Sampler, Texture, Unit, Wrap};
 use image;
 use std::ops::Deref;
 use std::path::Path;
 
-use resource::{Load, LoadError, Reload, ResCache, Result};
+use linear::{Vector2, Vector3, Vector4};
+use resource::{Load, LoadError, Reload, Res, ResCache, Result};
+use spline::{Interpolate, Spline, Time};
 
 // Common texture aliases.
 pub type TextureRGBA32F = Texture<Flat, Dim2, RGBA32F>;
 pub type TextureDepth32F = Texture<Flat, Dim2, Depth32F>;
+pub type Texture1DRGBA32F = Texture<Flat, Dim1, RGBA32F>;
 
 /// Load an RGBA texture from an image at a path.
 ///
@@ -80,3 +83,125 @@ impl Reload for TextureImage {
     (self.sampler, self.linearizer)
   }
 }
+
+/// Values that can be stored in an RGBA texel.
+///
+/// Missing components are set to `0`, except the alpha one, set to `1`.
+pub trait Texel {
+  fn to_texel(&self) -> [f32; 4];
+}
+
+impl Texel for f32 {
+  fn to_texel(&self) -> [f32; 4] {
+    [*self, 0., 0., 1.]
+  }
+}
+
+impl Texel for Vector2<f32> {
+  fn to_texel(&self) -> [f32; 4] {
+    [self.x, self.y, 0., 1.]
+  }
+}
+
+impl Texel for Vector3<f32> {
+  fn to_texel(&self) -> [f32; 4] {
+    [self.x, self.y, self.z, 1.]
+  }
+}
+
+impl Texel for Vector4<f32> {
+  fn to_texel(&self) -> [f32; 4] {
+    [self.x, self.y, self.z, self.w]
+  }
+}
+
+/// Sample a spline `width` times evenly over `[start, end]` and return the RGBA texels.
+///
+/// Outside of its keys, the spline is clamped to its first or last key. Texels that cannot be
+/// sampled are black and transparent.
+pub fn bake_spline<T>(spline: &Spline<T>, start: Time, end: Time, width: u32) -> Vec<f32> where T: Interpolate + Texel {
+  let mut texels = Vec::with_capacity(width as usize * 4);
+  let step = if width > 1 { (end - start) / (width - 1) as Time } else { 0. };
+
+  for i in 0..width {
+    let t = start + step * i as Time;
+    let texel = spline.clamped_sample(t).map(|x| x.to_texel()).unwrap_or([0., 0., 0., 0.]);
+
+    texels.extend_from_slice(&texel);
+  }
+
+  texels
+}
+
+/// 1D lookup texture baked from a spline.
+///
+/// Only 1D lookups are supported: the texture is a `Texture1DRGBA32F` of `width` texels, sampled by
+/// normalized time in shaders.
+///
+/// The texture doesn’t re-bake on its own when the spline is hot-reloaded: `SplineTexture::sync`
+/// must be called every frame, after `ResCache::sync`. It’s cheap when the spline hasn’t changed –
+/// a single generation check – and re-bakes the texture otherwise. Edits made to the spline in code
+/// don’t bump its generation; bake them with `SplineTexture::rebake`.
+pub struct SplineTexture<T> {
+  pub texture: Texture1DRGBA32F,
+  spline: Res<Spline<T>>,
+  // generation of the spline when it was last baked
+  generation: u64,
+  start: Time,
+  end: Time,
+  texels: Vec<f32>
+}
+
+impl<T> SplineTexture<T> where T: Interpolate + Texel {
+  /// Bake a spline over `[start, end]` into a texture of `width` texels.
+  pub fn new(spline: Res<Spline<T>>, start: Time, end: Time, width: u32, sampler: &Sampler) -> Result<Self> {
+    let texels = bake_spline(&spline.borrow(), start, end, width);
+    let texture = Texture::new(width, 0, sampler).map_err(|e| LoadError::ConversionFailed(format!("{:?}", e)))?;
+    texture.upload_raw(false, &texels);
+
+    Ok(SplineTexture {
+      texture: texture,
+      generation: spline.generation(),
+      spline: spline,
+      start: start,
+      end: end,
+      texels: texels
+    })
+  }
+
+  /// Re-bake the spline and upload the texels if the spline has been reloaded since it was last
+  /// baked.
+  ///
+  /// Return `true` if the texture was updated.
+  pub fn sync(&mut self) -> bool {
+    if self.spline.generation() == self.generation {
+      return false;
+    }
+
+    self.rebake();
+    true
+  }
+
+  /// Re-bake the spline and upload the texels.
+  pub fn rebake(&mut self) {
+    deb!("re-baking spline texture");
+
+    let width = (self.texels.len() / 4) as u32;
+    self.texels = bake_spline(&self.spline.borrow(), self.start, self.end, width);
+    self.texture.upload_raw(false, &self.texels);
+    self.generation = self.spline.generation();
+  }
+
+  /// Baked texels.
+  pub fn texels(&self) -> &[f32] {
+    &self.texels
+  }
+}
+
+impl<T> Deref for SplineTexture<T> {
+  type Target = Texture1DRGBA32F;
+
+  fn deref(&self) -> &Self::Target {
+    &self.texture
+  }
+}
@@ -1,9 +1,20 @@
 extern crate rand;
+extern crate serde_json;
 extern crate spectra;
 
 use rand::{Rng, thread_rng};
-use spectra::linear::{UnitQuaternion, Quaternion};
+use spectra::color::{ColorSpace, HSV, Oklab, RGB, RGBA, SRGB, blend_rgb};
+use spectra::compositing::Node;
+use spectra::edit::{Clip, Cut, FrameRate, Lint, Marker, MarkerCursor, Played, SubClips, Timeline, TimelineManifest, Track, TransitionRegistry, from_edl, from_otio, to_edl, to_otio};
+use spectra::linear::{UnitQuaternion, Quaternion, Scale, Vector3, Vector4};
+use spectra::resource::{Load, ResCache, Save};
 use spectra::spline::*;
+use spectra::tempo::{TempoChange, TempoMap, TimeSignature, TimeSpec};
+use spectra::texture::bake_spline;
+use std::collections::HashMap;
+use std::env::temp_dir;
+use std::fs::{File, create_dir_all};
+use std::io::Write;
 
 #[test]
 fn hold() {
@@ -58,3 +69,1000 @@ fn keys_sorted() {
     t = key.t;
   }
 }
+
+#[test]
+fn bezier() {
+  let spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::Bezier).with_handles(0., 1.),
+    Key::new(3., 3., Interpolation::Bezier).with_handles(2., 3.)
+  ]);
+
+  // handles on the chord at thirds give back a linear interpolation
+  assert_eq!(spline.sample(0.), Some(0.));
+  assert_eq!(spline.sample(1.5), Some(1.5));
+
+  let spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::Bezier).with_handles(0., 0.),
+    Key::new(1., 1., Interpolation::Bezier).with_handles(1., 1.)
+  ]);
+
+  // flat handles ease in and out
+  assert!(spline.sample(0.1).unwrap() < 0.1);
+  assert!(spline.sample(0.9).unwrap() > 0.9);
+}
+
+#[test]
+fn kochanek_bartels() {
+  let tcb = TCB::default();
+  let spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::KochanekBartels(tcb)),
+    Key::new(1., 1., Interpolation::KochanekBartels(tcb)),
+    Key::new(2., 2., Interpolation::KochanekBartels(tcb))
+  ]);
+
+  // boundary segments can be sampled and a straight line stays straight
+  assert_eq!(spline.sample(0.), Some(0.));
+  assert_eq!(spline.sample(0.5), Some(0.5));
+  assert_eq!(spline.sample(1.5), Some(1.5));
+
+  let tight = TCB::new(1., 0., 0.);
+  let spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::KochanekBartels(tight)),
+    Key::new(1., 1., Interpolation::KochanekBartels(tight)),
+    Key::new(2., 0., Interpolation::KochanekBartels(tight))
+  ]);
+
+  // full tension gives zero tangents at the keys
+  assert!(spline.sample(0.1).unwrap() < 0.1);
+  assert!(spline.sample(0.9).unwrap() > 0.9);
+  assert!(spline.sample(1.1).unwrap() <= 1.);
+
+  let continuous = TCB::new(0., 0.5, 0.);
+  let spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::KochanekBartels(continuous)),
+    Key::new(1., 1., Interpolation::KochanekBartels(continuous)),
+    Key::new(2., 3., Interpolation::KochanekBartels(continuous))
+  ]);
+
+  // tangents 1 and 0.25 * 1 + 0.75 * 2 = 1.75 at the first and second keys
+  assert!((spline.sample(0.5).unwrap() - 0.40625).abs() < 1e-5);
+}
+
+#[test]
+fn extrapolation() {
+  let keys = vec![
+    Key::new(0., 0., Interpolation::Linear),
+    Key::new(1., 1., Interpolation::Linear),
+    Key::new(2., 0., Interpolation::Linear)
+  ];
+
+  let spline = Spline::from_keys(keys.clone());
+  assert_eq!(spline.sample(-1.), None);
+  assert_eq!(spline.sample(3.), None);
+
+  let spline = Spline::from_keys(keys.clone()).with_extrapolation(Extrapolation::Constant, Extrapolation::Constant);
+  assert_eq!(spline.sample(-1.), Some(0.));
+  assert_eq!(spline.sample(2.), Some(0.));
+
+  let spline = Spline::from_keys(keys.clone()).with_extrapolation(Extrapolation::Cycle, Extrapolation::Cycle);
+  assert_eq!(spline.sample(2.5), Some(0.5));
+  assert_eq!(spline.sample(-0.5), Some(0.5));
+
+  let spline = Spline::from_keys(keys.clone()).with_extrapolation(Extrapolation::Linear, Extrapolation::Linear);
+  assert_eq!(spline.sample(-1.), Some(-1.));
+  assert_eq!(spline.sample(3.), Some(-1.));
+
+  let spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::Linear),
+    Key::new(1., 1., Interpolation::Linear)
+  ]).with_extrapolation(Extrapolation::PingPong, Extrapolation::CycleWithOffset);
+  assert_eq!(spline.sample(-0.25), Some(0.25));
+  assert_eq!(spline.sample(1.5), Some(1.5));
+
+  // linear extrapolation follows the end tangents, which are flat here
+  let spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::Bezier).with_handles(0., 0.),
+    Key::new(1., 1., Interpolation::Bezier).with_handles(1., 1.)
+  ]).with_extrapolation(Extrapolation::Linear, Extrapolation::Linear);
+  assert_eq!(spline.sample(-1.), Some(0.));
+  assert_eq!(spline.sample(2.), Some(1.));
+  assert_eq!(spline.sample_velocity(2.), Some(0.));
+
+  let empty: Spline<f32> = Spline::from_keys(Vec::new()).with_extrapolation(Extrapolation::Constant, Extrapolation::Constant);
+  assert_eq!(empty.sample(0.), None);
+  assert_eq!(empty.clamped_sample(0.), None);
+  assert_eq!(spline.clamped_sample(2.), Some(1.));
+}
+
+#[test]
+fn derivatives() {
+  let spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::Linear),
+    Key::new(2., 4., Interpolation::Cosine),
+    Key::new(4., 0., Interpolation::Linear)
+  ]);
+
+  assert_eq!(spline.sample_velocity(1.), Some(2.));
+  assert_eq!(spline.sample_acceleration(1.), Some(0.));
+  assert_eq!(spline.sample_velocity(5.), None);
+
+  // the cosine segment peaks in velocity halfway
+  let v = spline.sample_velocity(3.).unwrap();
+  assert!((v + 4. * std::f32::consts::PI * 0.25).abs() < 1e-5);
+
+  let spline = Spline::from_keys(vec![
+    Key::new(0., Vector3::new(0., 0., 0.), Interpolation::Bezier).with_handles(Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.)),
+    Key::new(3., Vector3::new(3., 0., 0.), Interpolation::Bezier).with_handles(Vector3::new(2., 0., 0.), Vector3::new(3., 0., 0.))
+  ]).with_extrapolation(Extrapolation::Linear, Extrapolation::Constant);
+
+  assert_eq!(spline.sample_velocity(1.5), Some(Vector3::new(1., 0., 0.)));
+  assert_eq!(spline.sample_velocity(-1.), Some(Vector3::new(1., 0., 0.)));
+  assert_eq!(spline.sample_velocity(4.), Some(Vector3::new(0., 0., 0.)));
+}
+
+#[test]
+fn arc_length() {
+  let spline = Spline::from_keys(vec![
+    Key::new(0., Vector3::new(0., 0., 0.), Interpolation::Linear),
+    Key::new(1., Vector3::new(3., 0., 0.), Interpolation::Linear),
+    Key::new(4., Vector3::new(3., 3., 0.), Interpolation::Linear)
+  ]);
+  let arc_length = ArcLength::new(&spline, 8);
+
+  assert!((arc_length.length() - 6.).abs() < 1e-5);
+  assert!((arc_length.distance_at(1.) - 3.).abs() < 1e-5);
+  assert!((arc_length.time_at(4.5) - 2.5).abs() < 1e-5);
+  assert_eq!(arc_length.sample_normalized(&spline, 1.), Some(Vector3::new(3., 3., 0.)));
+
+  let speed = Spline::from_keys(vec![
+    Key::new(0., 2., Interpolation::Linear),
+    Key::new(10., 2., Interpolation::Linear)
+  ]);
+  let distance = speed_to_distance(&speed, 0.5).unwrap();
+
+  assert_eq!(distance.sample(1.), Some(2.));
+  let p = arc_length.sample_along(&spline, &distance, 1.).unwrap();
+  assert!((p.x - 2.).abs() < 1e-5 && p.y == 0.);
+
+  assert!(speed_to_distance(&speed, 0.).is_none());
+
+  // steps far below the spacing of floats around the keys still move forward
+  let late_speed = Spline::from_keys(vec![
+    Key::new(1000., 1., Interpolation::Linear),
+    Key::new(1000.001, 1., Interpolation::Linear)
+  ]);
+  assert!(speed_to_distance(&late_speed, 1e-5).unwrap().len() > 1);
+}
+
+#[test]
+fn edit_keys() {
+  let mut spline = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::Linear),
+    Key::new(10., 10., Interpolation::Linear)
+  ]);
+
+  assert_eq!(spline.insert_key(Key::new(5., 0., Interpolation::Linear)), 1);
+  assert_eq!(spline.sample(5.), Some(0.));
+
+  assert_eq!(spline.move_key(1, 20.), Some(2));
+  assert_eq!(spline.nearest_key(16.), Some(2));
+  assert_eq!(spline.nearest_key(-3.), Some(0));
+
+  assert_eq!(spline.set_value(1, 20.), Some(10.));
+  assert_eq!(spline.sample(5.), Some(10.));
+
+  assert!(spline.remove_key_at(19., 0.5).is_none());
+  assert_eq!(spline.remove_key_at(19.75, 0.5).map(|k| k.t), Some(20.));
+  assert_eq!(spline.len(), 2);
+
+  let times: Vec<_> = spline.keys().iter().map(|k| k.t).collect();
+  assert_eq!(times, vec![0., 10.]);
+}
+
+#[test]
+fn save_load() {
+  let dir = temp_dir().join("spectra-spline-save");
+  create_dir_all(&dir).unwrap();
+  let mut cache = ResCache::new(&dir);
+
+  let path = dir.join("orientation.json");
+  let q = UnitQuaternion::new(&Quaternion::new(0., 1., 0., 0.));
+  let spline = Spline::from_keys(vec![
+    Key::new(0., q, Interpolation::Linear),
+    Key::new(1., q, Interpolation::Step(0.5))
+  ]).with_extrapolation(Extrapolation::Cycle, Extrapolation::Constant);
+
+  spline.save(&path).unwrap();
+  let loaded: Spline<UnitQuaternion<f32>> = Load::load(&path, &mut cache, ()).unwrap();
+
+  assert_eq!(loaded.extrapolation(), (Extrapolation::Cycle, Extrapolation::Constant));
+  assert_eq!(loaded.keys()[1].value.quaternion().i, 1.);
+
+  let path = dir.join("scale.json");
+  let spline = Spline::from_keys(vec![
+    Key::new(0., Scale::uni(1.), Interpolation::Bezier).with_handles(Scale::uni(1.), Scale::uni(2.)),
+    Key::new(2., Scale::new(1., 2., 3.), Interpolation::Linear)
+  ]);
+
+  spline.save(&path).unwrap();
+  let loaded: Spline<Scale> = Load::load(&path, &mut cache, ()).unwrap();
+
+  assert_eq!(loaded.keys()[0].handles.map(|h| h.out_handle), Some(Scale::uni(2.)));
+  assert_eq!(loaded.keys()[1].value, Scale::new(1., 2., 3.));
+  assert_eq!(loaded.sample(1.), spline.sample(1.));
+}
+
+#[test]
+fn cursor() {
+  let spline = Spline::from_keys((0..100).map(|i| Key::new(i as f32, (i * i) as f32, Interpolation::Linear)).collect());
+  let mut cursor = SplineCursor::new();
+
+  // forward playback, then a seek backwards
+  let times = (0..400).map(|i| i as f32 * 0.25).chain(vec![42.5, 3.25, -1., 99.]);
+
+  for t in times {
+    assert_eq!(cursor.sample(&spline, t), spline.sample(t));
+  }
+}
+
+// Rotation of the given angle around the Z axis.
+fn z_rotation(angle: f32) -> UnitQuaternion<f32> {
+  UnitQuaternion::new(&Quaternion::new((angle * 0.5).cos(), 0., 0., (angle * 0.5).sin()))
+}
+
+fn same_rotation(a: UnitQuaternion<f32>, b: UnitQuaternion<f32>) -> bool {
+  let (a, b) = (a.quaternion(), b.quaternion());
+  (a.w * b.w + a.i * b.i + a.j * b.j + a.k * b.k).abs() > 1. - 1e-4
+}
+
+#[test]
+fn quaternion_shortest_path() {
+  let deg = std::f32::consts::PI / 180.;
+  let a = z_rotation(0.);
+  let b = z_rotation(90. * deg);
+  let neg_b = UnitQuaternion::new(&Quaternion::new(-b.quaternion().w, 0., 0., -b.quaternion().k));
+
+  assert!(same_rotation(Interpolate::lerp(a, neg_b, 0.5), z_rotation(45. * deg)));
+}
+
+#[test]
+fn quaternion_squad() {
+  let deg = std::f32::consts::PI / 180.;
+  let spline = Spline::from_keys(vec![
+    Key::new(0., z_rotation(0.), Interpolation::CatmullRom),
+    Key::new(1., z_rotation(90. * deg), Interpolation::CatmullRom),
+    Key::new(2., z_rotation(180. * deg), Interpolation::CatmullRom),
+    Key::new(3., z_rotation(270. * deg), Interpolation::CatmullRom)
+  ]);
+
+  // constant angular velocity is preserved
+  assert!(same_rotation(spline.sample(1.5).unwrap(), z_rotation(135. * deg)));
+  assert!(same_rotation(spline.sample(1.25).unwrap(), z_rotation(112.5 * deg)));
+}
+
+#[test]
+fn easing() {
+  let eases = [
+    Ease::QuadIn, Ease::QuadOut, Ease::QuadInOut,
+    Ease::CubicIn, Ease::CubicOut, Ease::CubicInOut,
+    Ease::ExpoIn, Ease::ExpoOut, Ease::ExpoInOut,
+    Ease::BackIn, Ease::BackOut, Ease::BackInOut,
+    Ease::ElasticIn, Ease::ElasticOut, Ease::ElasticInOut,
+    Ease::BounceIn, Ease::BounceOut, Ease::BounceInOut
+  ];
+
+  for ease in &eases {
+    assert!(ease.apply(0.).abs() < 1e-3, "{:?}", ease);
+    assert!((ease.apply(1.) - 1.).abs() < 1e-3, "{:?}", ease);
+  }
+
+  let keys: Vec<Key<f32>> = serde_json::from_str(r#"[
+    { "t": 0.0, "value": 0.0, "interpolation": { "ease": "cubic_in" } },
+    { "t": 2.0, "value": 8.0 }
+  ]"#).unwrap();
+  let spline = Spline::from_keys(keys);
+
+  assert_eq!(spline.sample(1.), Some(1.));
+}
+
+#[test]
+fn animation_clip() {
+  let mut clip = AnimationClip::new();
+
+  clip.add_channel("position", Channel::Vec3(Spline::from_keys(vec![
+    Key::new(0., Vector3::new(0., 0., 0.), Interpolation::Linear),
+    Key::new(1., Vector3::new(0., 2., 0.), Interpolation::Linear)
+  ])));
+  clip.add_channel("fade", Channel::Float(Spline::from_keys(vec![
+    Key::new(0., 1., Interpolation::Linear),
+    Key::new(2., 0., Interpolation::Linear)
+  ])));
+
+  assert_eq!(clip.sample_channel("position", 0.5), Some(Vector3::new(0., 1., 0.)));
+  assert_eq!(clip.sample_channel::<f32>("position", 0.5), None);
+  assert_eq!(clip.sample_channel("fade", 1.), Some(0.5));
+  assert_eq!(clip.sample(0.5).len(), 2);
+  assert_eq!(clip.sample(1.5).len(), 1);
+}
+
+#[test]
+fn animation_clip_load() {
+  let dir = temp_dir().join("spectra-animation-load");
+  create_dir_all(&dir).unwrap();
+  let mut cache = ResCache::new(&dir);
+
+  let path = dir.join("object.json");
+  let mut file = File::create(&path).unwrap();
+  file.write_all(br#"{
+    "position": { "vec3": [{ "t": 0, "value": [0, 0, 0] }, { "t": 1, "value": [0, 2, 0] }] },
+    "scale": { "scale": { "keys": [{ "t": 0, "value": [1, 1, 1] }], "post": "constant" } },
+    "orientation": { "orientation": [{ "t": 0, "value": [1, 0, 0, 0] }, { "t": 1, "value": [1, 0, 0, 0] }] }
+  }"#).unwrap();
+  drop(file);
+
+  let clip: AnimationClip = Load::load(&path, &mut cache, ()).unwrap();
+  assert_eq!(clip.sample_channel("position", 0.5), Some(Vector3::new(0., 1., 0.)));
+  assert_eq!(clip.sample_channel("scale", 3.), Some(Scale::uni(1.)));
+  assert_eq!(clip.sample(0.5).len(), 3);
+
+  // reloading picks up the new content of the file
+  let mut file = File::create(&path).unwrap();
+  file.write_all(br#"{ "fade": { "float": [{ "t": 0, "value": 1 }, { "t": 2, "value": 0 }] } }"#).unwrap();
+  drop(file);
+
+  let clip: AnimationClip = Load::load(&path, &mut cache, ()).unwrap();
+  assert!(clip.channel("position").is_none());
+  assert_eq!(clip.sample_channel("fade", 1.), Some(0.5));
+
+  let mut file = File::create(&path).unwrap();
+  file.write_all(br#"{ "fade": { "color": [] } }"#).unwrap();
+  drop(file);
+
+  assert!(AnimationClip::load(&path, &mut cache, ()).is_err());
+}
+
+#[test]
+fn fit() {
+  // a straight line only needs its end points
+  let line: Vec<_> = (0..100).map(|i| (i as f32, 2. * i as f32)).collect();
+  let spline = fit_spline(&line, 1e-3, Interpolation::Linear);
+
+  assert_eq!(spline.len(), 2);
+
+  let wave: Vec<_> = (0..200).map(|i| {
+    let t = i as f32 * 0.05;
+    (t, t.sin())
+  }).collect();
+
+  for &interpolation in &[Interpolation::Linear, Interpolation::CatmullRom] {
+    let spline = fit_spline(&wave, 0.01, interpolation);
+
+    assert!(spline.len() < wave.len() / 2);
+
+    for &(t, v) in &wave[..wave.len() - 1] {
+      assert!((spline.sample(t).unwrap() - v).abs() <= 0.01);
+    }
+  }
+}
+
+#[test]
+fn tempo_map() {
+  // 4/4 at 120 BPM, then 3/4 at 60 BPM from the 5th bar on
+  let tempo = TempoMap::with_changes(120., TimeSignature(4, 4), &[
+    TempoChange { beat: 16., bpm: Some(60.), signature: Some(TimeSignature(3, 4)) }
+  ]);
+
+  assert_eq!(tempo.beats_to_secs(8.), 4.);
+  assert_eq!(tempo.secs_to_beats(4.), 8.);
+  assert_eq!(tempo.beats_to_secs(19.), 11.);
+  assert_eq!(tempo.secs_to_beats(11.), 19.);
+  assert_eq!(tempo.beats_to_bars(16.), 4.);
+  assert_eq!(tempo.beats_to_bars(22.), 6.);
+  assert_eq!(tempo.bars_to_beats(6.), 22.);
+  assert_eq!(tempo.bars_to_secs(5.), 11.);
+  assert_eq!(tempo.bar_beat(12.), (5, 1.));
+  assert_eq!(tempo.bpm(10.), 60.);
+
+  assert_eq!(TimeSpec::Beats { beats: 19. }.to_secs(&tempo), 11.);
+  assert_eq!(TimeSpec::Secs(3.).to_secs(&tempo), 3.);
+
+  // tempo files with a null or negative tempo, or bars without beats, fail to load
+  let dir = temp_dir().join("spectra-tempo-load");
+  create_dir_all(&dir).unwrap();
+  let mut cache = ResCache::new(&dir);
+  let path = dir.join("song.json");
+
+  for json in &[
+    r#"{ "bpm": 0 }"#,
+    r#"{ "bpm": 120, "signature": [0, 4] }"#,
+    r#"{ "bpm": 120, "changes": [{ "beat": 16, "bpm": -60 }] }"#
+  ] {
+    File::create(&path).unwrap().write_all(json.as_bytes()).unwrap();
+    assert!(<TempoMap as Load>::load(&path, &mut cache, ()).is_err());
+  }
+
+  File::create(&path).unwrap().write_all(br#"{ "bpm": 120, "signature": [3, 4] }"#).unwrap();
+  assert_eq!(<TempoMap as Load>::load(&path, &mut cache, ()).unwrap(), TempoMap::new(120., TimeSignature(3, 4)));
+
+  let spec: TimeSpec = serde_json::from_str("{ \"bars\": 5 }").unwrap();
+  assert_eq!(spec, TimeSpec::Bars { bars: 5. });
+
+  // a 4-beat cut placed after the tempo change lasts 4 seconds at 60 BPM
+  let mut manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [{ "cuts": [{ "in_time": { "beats": 2 }, "out_time": { "beats": 6 }, "inst_time": { "bars": 4 }, "clip": "a" }] }]
+  }"#).unwrap();
+  manifest.resolve_times(&tempo);
+
+  let cut = &manifest.tracks[0].cuts[0];
+  assert_eq!((cut.in_time, cut.out_time, cut.inst_time), (TimeSpec::Secs(2.), TimeSpec::Secs(6.), TimeSpec::Secs(8.)));
+
+  // a timeline built from a manifest in musical time writes its times back in the same units
+  let mut manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tempo": "song",
+    "tracks": [{ "cuts": [{ "in_time": { "beats": 2 }, "out_time": { "beats": 6 }, "inst_time": { "bars": 4 }, "clip": "a" }] }],
+    "overlaps": [{ "inst_time": { "beats": 18 }, "dur": { "beats": 1 }, "transition": "crossfade" }],
+    "markers": [{ "time": { "bars": 5 }, "label": "drop" }]
+  }"#).unwrap();
+  manifest.tempo_map = Some(tempo);
+
+  let clip = Clip::new(|_| Node::Color(RGBA::new(0., 0., 0., 1.)));
+  let mut mapping = HashMap::new();
+  mapping.insert("a".to_owned(), &clip);
+
+  let timeline = Timeline::from_manifest(&manifest, &mapping, &TransitionRegistry::new());
+  assert_eq!(timeline.tracks()[0].cuts()[0].end_time(), 12.);
+  assert_eq!(timeline.markers()[0].time, 11.);
+  assert_eq!(timeline.tempo(), Some("song"));
+  assert_eq!(timeline.to_manifest(&mapping), manifest);
+}
+
+#[test]
+fn bake() {
+  let spline = Spline::from_keys(vec![
+    Key::new(0., Vector4::new(0., 0., 0., 1.), Interpolation::Linear),
+    Key::new(1., Vector4::new(1., 0.5, 0., 1.), Interpolation::Linear)
+  ]);
+
+  let texels = bake_spline(&spline, 0., 2., 5);
+
+  assert_eq!(texels.len(), 20);
+  assert_eq!(&texels[0..4], &[0., 0., 0., 1.]);
+  assert_eq!(&texels[4..8], &[0.5, 0.25, 0., 1.]);
+  // clamped to the last key
+  assert_eq!(&texels[8..12], &[1., 0.5, 0., 1.]);
+  assert_eq!(&texels[16..20], &[1., 0.5, 0., 1.]);
+
+  let ramp = Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::Linear),
+    Key::new(1., 1., Interpolation::Linear)
+  ]);
+
+  assert_eq!(bake_spline(&ramp, 0., 1., 3), vec![0., 0., 0., 1., 0.5, 0., 0., 1., 1., 0., 0., 1.]);
+}
+
+#[test]
+fn color_spaces() {
+  let close = |a: RGB, b: RGB| (a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs() < 1e-4;
+  let orange = RGB::new(0.8, 0.3, 0.05);
+
+  assert!((SRGB::from_linear(RGB::new(0.5, 0.5, 0.5)).r - 0.7354).abs() < 1e-3);
+  assert!(close(SRGB::from_linear(orange).to_linear(), orange));
+  assert!(close(HSV::from_linear(orange).to_linear(), orange));
+  assert!(close(Oklab::from_linear(orange).to_linear(), orange));
+  assert!((Oklab::from_linear(RGB::new(1., 1., 1.)).l - 1.).abs() < 1e-3);
+
+  let red = RGB::new(1., 0., 0.);
+  let blue = RGB::new(0., 0., 1.);
+
+  // hues go the short way around: red to blue passes through magenta, not green
+  let mid = blend_rgb(red, blue, 0.5, ColorSpace::HSV);
+  assert!(close(mid, RGB::new(1., 0., 1.)));
+
+  // blending in Oklab keeps more saturation than in linear RGB
+  let saturation = |c: RGB| HSV::from_linear(c).s;
+  assert!(saturation(blend_rgb(red, RGB::new(0., 1., 1.), 0.5, ColorSpace::Oklab)) > saturation(blend_rgb(red, RGB::new(0., 1., 1.), 0.5, ColorSpace::Linear)));
+
+  let spline = Spline::from_keys(vec![
+    Key::new(0., red, Interpolation::Blend(ColorSpace::HSV)),
+    Key::new(1., blue, Interpolation::Linear)
+  ]);
+  assert!(close(spline.sample(0.5).unwrap(), mid));
+
+  let interpolation: Interpolation = serde_json::from_str("{ \"blend\": \"oklab\" }").unwrap();
+  match interpolation {
+    Interpolation::Blend(ColorSpace::Oklab) => (),
+    _ => panic!("wrong interpolation")
+  }
+}
+
+#[test]
+fn nested_timeline() {
+  let shot = Clip::new(|ctx| Node::Color(RGBA::new(ctx.local as f32, 0., 0., 1.)));
+  let mut mapping = HashMap::new();
+  mapping.insert("shot".to_owned(), &shot);
+
+  let scene: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [{ "cuts": [{ "in_time": 0, "out_time": 10, "inst_time": 1, "clip": "shot" }] }]
+  }"#).unwrap();
+  let mut demo: TimelineManifest = serde_json::from_str(r#"{
+    "timelines": ["scene"],
+    "tracks": [{ "cuts": [{ "in_time": 1, "out_time": 5, "inst_time": 4, "clip": "scene" }] }]
+  }"#).unwrap();
+  demo.sub_manifests.insert("scene".to_owned(), scene);
+
+  let transitions = TransitionRegistry::new();
+  let sub_clips = SubClips::new(&demo, &mapping, &transitions).unwrap();
+  let timeline = Timeline::from_nested_manifest(&demo, &mapping, &transitions, &sub_clips);
+
+  // the scene starts at 4s, 1s into its own time; its shot starts at 1s
+  match timeline.play(6.) {
+    Played::Resolved(Node::Color(color)) => assert_eq!(color.x, 2.),
+    _ => panic!("the scene should be playing")
+  }
+
+  match timeline.play(2.) {
+    Played::Inactive => (),
+    _ => panic!("the scene shouldn’t be playing")
+  }
+
+  // sub-timelines cannot nest other timelines
+  let mut scene = demo.sub_manifests["scene"].clone();
+  scene.timelines.push("shot_list".to_owned());
+  demo.sub_manifests.insert("scene".to_owned(), scene);
+
+  assert!(SubClips::new(&demo, &mapping, &transitions).is_err());
+}
+
+#[test]
+fn clip_context() {
+  let clip = Clip::new(|_| Node::Color(RGBA::new(0., 0., 0., 1.)));
+  let cut = Cut::new(2., 6., 10., &clip);
+  let ctx = cut.context(11.);
+
+  assert_eq!(ctx.global, 11.);
+  assert_eq!(ctx.local, 3.);
+  assert_eq!(ctx.progress, 0.25);
+  assert_eq!(ctx.dur, 4.);
+}
+
+// Red and blue clips, named after their colors, shared by the timeline tests.
+struct ColorClips {
+  red: Clip<'static, 'static>,
+  blue: Clip<'static, 'static>
+}
+
+impl ColorClips {
+  fn new() -> Self {
+    ColorClips {
+      red: Clip::new(|_| Node::Color(RGBA::new(1., 0., 0., 1.))),
+      blue: Clip::new(|_| Node::Color(RGBA::new(0., 0., 1., 1.)))
+    }
+  }
+
+  fn mapping(&self) -> HashMap<String, &Clip<'static, 'static>> {
+    let mut mapping = HashMap::new();
+    mapping.insert("red".to_owned(), &self.red);
+    mapping.insert("blue".to_owned(), &self.blue);
+    mapping
+  }
+}
+
+#[test]
+fn manifest_transitions() {
+  let clips = ColorClips::new();
+  let mapping = clips.mapping();
+
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [
+      { "cuts": [{ "in_time": 0, "out_time": 4, "inst_time": 0, "clip": "red" }] },
+      { "cuts": [{ "in_time": 0, "out_time": 4, "inst_time": 3, "clip": "blue" }] }
+    ],
+    "overlaps": [{ "inst_time": 3, "dur": 1, "transition": "first" }]
+  }"#).unwrap();
+
+  // only an unknown transition: nodes cannot be folded
+  let timeline = Timeline::from_manifest(&manifest, &mapping, &TransitionRegistry::new());
+  match timeline.play(3.5) {
+    Played::NoOverlap => (),
+    _ => panic!("the overlap shouldn’t exist")
+  }
+
+  let mut transitions = TransitionRegistry::new();
+  transitions.register("first", |mut nodes, progress| {
+    assert_eq!(progress, 0.5);
+    nodes.remove(0)
+  });
+
+  let timeline = Timeline::from_manifest(&manifest, &mapping, &transitions);
+  match timeline.play(3.5) {
+    Played::Resolved(Node::Color(color)) => assert_eq!(color.x, 1.),
+    _ => panic!("the overlap should resolve to the first track")
+  }
+
+  // with three nodes, each pair of consecutive nodes takes half of the overlap
+  let wipe = TransitionRegistry::new().get("wipe").unwrap().clone();
+  let nodes = vec![
+    Node::Color(RGBA::new(1., 0., 0., 1.)),
+    Node::Color(RGBA::new(0., 1., 0., 1.)),
+    Node::Color(RGBA::new(0., 0., 1., 1.))
+  ];
+  match (*wipe)(nodes, 0.75) {
+    Node::Wipe(from, to, progress, _) => {
+      match (*from, *to) {
+        (Node::Color(from), Node::Color(to)) => {
+          assert_eq!(from.y, 1.);
+          assert_eq!(to.z, 1.);
+        }
+        _ => panic!("the wipe should go from the second node to the third one")
+      }
+      assert_eq!(progress, 0.5);
+    }
+    _ => panic!("the transition should be a wipe")
+  }
+}
+
+#[test]
+fn lint_timeline() {
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [
+      {
+        "cuts": [
+          { "in_time": 0, "out_time": 4, "inst_time": 0, "clip": "a" },
+          { "in_time": 0, "out_time": 2, "inst_time": 3, "clip": "b" },
+          { "in_time": 2, "out_time": 1, "inst_time": 6, "clip": "b" }
+        ]
+      },
+      { "cuts": [{ "in_time": 0, "out_time": 3, "inst_time": 7, "clip": "c" }] }
+    ],
+    "overlaps": [{ "inst_time": 7, "dur": 1, "transition": "dissolve" }]
+  }"#).unwrap();
+
+  assert_eq!(manifest.lint(), vec![
+    Lint::InvalidCut { track: 0, cut: 2, in_time: 2., out_time: 1. },
+    Lint::OverlappingCuts { track: 0, cuts: (0, 1), start: 3., end: 4. },
+    Lint::UncoveredOverlap { tracks: vec![0], start: 3., end: 4. },
+    Lint::Gap { start: 5., end: 7. }
+  ]);
+
+  let mut mapping = HashMap::new();
+  mapping.insert("a".to_owned(), ());
+  mapping.insert("b".to_owned(), ());
+
+  assert_eq!(manifest.lint_names(&mapping, &TransitionRegistry::new()), vec![
+    Lint::MissingClip { track: 1, cut: 0, clip: "c".to_owned() },
+    Lint::UnknownTransition { overlap: 0, transition: "dissolve".to_owned() }
+  ]);
+
+  // touching cuts never play together, and neither do cuts of a muted track
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [
+      { "cuts": [{ "in_time": 0, "out_time": 2, "inst_time": 0, "clip": "a" }] },
+      { "cuts": [{ "in_time": 0, "out_time": 2, "inst_time": 2, "clip": "b" }] },
+      { "cuts": [{ "in_time": 0, "out_time": 4, "inst_time": 0, "clip": "c" }], "muted": true }
+    ]
+  }"#).unwrap();
+  assert!(manifest.lint().is_empty());
+
+  // a soloed track plays alone; once every track plays, the third one overlaps the others
+  let mut manifest = manifest;
+  manifest.tracks[2].muted = false;
+  manifest.tracks[2].solo = true;
+  assert!(manifest.lint().is_empty());
+
+  manifest.tracks[2].solo = false;
+  assert_eq!(manifest.lint(), vec![Lint::UncoveredOverlap { tracks: vec![0, 2], start: 0., end: 2. }, Lint::UncoveredOverlap { tracks: vec![1, 2], start: 2., end: 4. }]);
+}
+
+#[test]
+fn edit_timeline() {
+  let clip = Clip::new(|_| Node::Color(RGBA::new(0., 0., 0., 1.)));
+  let times = |track: &Track| -> Vec<(f64, f64, f64)> {
+    track.cuts().iter().map(|c| (c.in_time, c.out_time, c.inst_time)).collect()
+  };
+
+  let mut track = Track::new();
+  track.add_cut(Cut::new(0., 4., 6., &clip));
+  track.add_cut(Cut::new(0., 6., 0., &clip));
+  assert_eq!(times(&track), vec![(0., 6., 0.), (0., 4., 6.)]);
+
+  assert_eq!(track.split(2.), Some(1));
+  assert_eq!(track.split(6.), None);
+  assert_eq!(times(&track), vec![(0., 2., 0.), (2., 6., 2.), (0., 4., 6.)]);
+
+  assert_eq!(track.trim_in(1, 3.), Some(1));
+  assert_eq!(track.trim_out(0, 1.), Some(0));
+  assert_eq!(track.trim_out(0, -1.), None);
+  assert_eq!(times(&track), vec![(0., 1., 0.), (3., 6., 3.), (0., 4., 6.)]);
+
+  assert!(track.slip(2, 1.));
+  assert!(track.roll(1, -1.));
+  assert!(!track.roll(2, 1.));
+  assert_eq!(times(&track), vec![(0., 1., 0.), (3., 5., 3.), (0., 5., 5.)]);
+
+  assert!(track.ripple_delete(1).is_some());
+  assert_eq!(times(&track), vec![(0., 1., 0.), (0., 5., 3.)]);
+
+  let mut timeline = Timeline::new();
+  timeline.add_track(track);
+  timeline.add_track(Track::new());
+  assert_eq!(timeline.move_cut(0, 1, 1), Some(0));
+  assert_eq!(timeline.move_cut(0, 1, 1), None);
+
+  let mut mapping = HashMap::new();
+  mapping.insert("black".to_owned(), &clip);
+
+  let manifest = timeline.to_manifest(&mapping);
+  let rebuilt = Timeline::from_manifest(&manifest, &mapping, &TransitionRegistry::new());
+  assert_eq!(rebuilt.to_manifest(&mapping), manifest);
+  assert_eq!(times(&rebuilt.tracks()[1]), vec![(0., 5., 3.)]);
+
+  // the two halves of a split cut meet at the split time without needing any overlap
+  let mut split = Timeline::new();
+  let mut track = Track::new();
+  track.add_cut(Cut::new(0., 4., 0., &clip));
+  assert_eq!(track.split(1.5), Some(1));
+  split.add_track(track);
+  match split.play(1.5) {
+    Played::Resolved(_) => (),
+    _ => panic!("only the second half should play at the split time")
+  }
+
+  // overlaps and markers after the removed cut move along with the next cuts
+  let mut timeline = Timeline::new();
+  let mut track = Track::new();
+  track.add_cut(Cut::new(0., 2., 0., &clip));
+  track.add_cut(Cut::new(0., 2., 2., &clip));
+  timeline.add_track(track);
+  timeline.add_overlap(TransitionRegistry::new().overlap("crossfade", 3., 1.).unwrap());
+  timeline.add_marker(Marker::new(1., "inside", None));
+  timeline.add_marker(Marker::new(2.5, "after", None));
+
+  assert!(timeline.ripple_delete(0, 0).is_some());
+  assert!(timeline.ripple_delete(1, 0).is_none());
+  assert_eq!(times(&timeline.tracks()[0]), vec![(0., 2., 0.)]);
+  assert_eq!(timeline.overlaps()[0].inst_time, 1.);
+  assert_eq!(timeline.markers().iter().map(|m| m.time).collect::<Vec<_>>(), vec![0.5, 1.]);
+}
+
+#[test]
+fn markers() {
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [],
+    "markers": [
+      { "time": 2.5, "label": "reset" },
+      { "time": 0, "label": "start" },
+      { "time": 1, "label": "flash", "payload": { "intensity": 2 } },
+      { "time": 1, "label": "burst" }
+    ]
+  }"#).unwrap();
+  let mapping = HashMap::new();
+  let timeline: Timeline = Timeline::from_manifest(&manifest, &mapping, &TransitionRegistry::new());
+  let labels = |markers: &[Marker]| -> Vec<String> { markers.iter().map(|m| m.label.clone()).collect() };
+
+  assert_eq!(labels(timeline.crossed_markers(0., 1.)), vec!["start"]);
+  assert_eq!(labels(timeline.crossed_markers(1., 3.)), vec!["flash", "burst", "reset"]);
+  assert!(timeline.crossed_markers(3., 1.).is_empty());
+  assert_eq!(timeline.markers()[1].payload, Some(serde_json::from_str("{ \"intensity\": 2 }").unwrap()));
+
+  let mut cursor = MarkerCursor::new(0.);
+  assert_eq!(labels(cursor.advance(&timeline, 0.5)), vec!["start"]);
+  assert!(cursor.advance(&timeline, 0.5).is_empty());
+
+  // scrubbing backwards and seeking don’t trigger anything
+  cursor.seek(2.);
+  assert!(cursor.advance(&timeline, 1.5).is_empty());
+  assert_eq!(labels(cursor.advance(&timeline, 3.)), vec!["reset"]);
+}
+
+#[test]
+fn mute_solo() {
+  let clips = ColorClips::new();
+  let mapping = clips.mapping();
+
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [
+      { "cuts": [{ "in_time": 0, "out_time": 4, "inst_time": 0, "clip": "red" }] },
+      { "cuts": [{ "in_time": 0, "out_time": 4, "inst_time": 0, "clip": "blue" }], "muted": true }
+    ]
+  }"#).unwrap();
+  let mut timeline = Timeline::from_manifest(&manifest, &mapping, &TransitionRegistry::new());
+  let played_red = |timeline: &Timeline| match timeline.play(1.) {
+    Played::Resolved(Node::Color(color)) => color.x,
+    _ => -1.
+  };
+
+  assert_eq!(played_red(&timeline), 1.);
+
+  assert_eq!(timeline.toggle_mute(1), Some(false));
+  assert_eq!(timeline.toggle_solo(1), Some(true));
+  assert_eq!(played_red(&timeline), 0.);
+
+  // a muted track doesn’t play, even soloed
+  assert_eq!(timeline.toggle_mute(1), Some(true));
+  match timeline.play(1.) {
+    Played::Inactive => (),
+    _ => panic!("nothing should play")
+  }
+
+  assert_eq!(timeline.toggle_mute(3), None);
+  assert_eq!(timeline.to_manifest(&mapping).tracks[1].solo, true);
+}
+
+#[test]
+fn cut_time_remapping() {
+  let clip = Clip::new(|_| Node::Color(RGBA::new(0., 0., 0., 1.)));
+  let mut cut = Cut::new(2., 6., 10., &clip);
+
+  cut.set_speed(2.);
+  assert_eq!(cut.span(), 4.);
+  assert_eq!(cut.dur(), 2.);
+  assert_eq!(cut.clip_time(11.), 4.);
+
+  cut.reverse = true;
+  assert_eq!(cut.clip_time(10.), 6.);
+  assert_eq!(cut.clip_time(11.5), 3.);
+
+  // play the first half of the clip, then freeze on its middle
+  cut.reverse = false;
+  cut.remap = Some(Spline::from_keys(vec![
+    Key::new(0., 0., Interpolation::Linear),
+    Key::new(0.5, 0.5, Interpolation::Linear),
+    Key::new(1., 0.5, Interpolation::Linear)
+  ]));
+  assert_eq!(cut.clip_time(10.5), 3.);
+  assert_eq!(cut.clip_time(11.5), 4.);
+  assert_eq!(cut.clip_time(12.), 4.);
+  assert_eq!(cut.context(11.5).progress, 0.75);
+
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [{ "cuts": [{ "in_time": 0, "out_time": 4, "inst_time": 0, "clip": "a", "speed": 0.5, "reverse": true }] }]
+  }"#).unwrap();
+  let cut_manifest = &manifest.tracks[0].cuts[0];
+
+  assert_eq!((cut_manifest.speed, cut_manifest.reverse, cut_manifest.remap.is_none()), (0.5, true, true));
+  // the cut lasts 8 seconds at half speed
+  assert!(manifest.lint().is_empty());
+
+  // remappings are written like spline files: their keys get sorted and they can extrapolate
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [{
+      "cuts": [
+        { "in_time": 0, "out_time": 4, "inst_time": 0, "clip": "a", "remap": [{ "t": 1, "value": 1 }, { "t": 0, "value": 0 }] },
+        { "in_time": 0, "out_time": 4, "inst_time": 4, "clip": "a", "remap": { "keys": [{ "t": 0, "value": 0 }, { "t": 0.5, "value": 1 }], "post": "constant" } }
+      ]
+    }]
+  }"#).unwrap();
+  let remap = manifest.tracks[0].cuts[0].remap.as_ref().unwrap();
+  assert_eq!(remap.keys().iter().map(|key| key.t).collect::<Vec<_>>(), vec![0., 1.]);
+  let remap = manifest.tracks[0].cuts[1].remap.as_ref().unwrap();
+  assert_eq!(remap.sample(0.75), Some(1.));
+
+  let json = serde_json::to_string(&manifest).unwrap();
+  assert_eq!(serde_json::from_str::<TimelineManifest>(&json).unwrap(), manifest);
+
+  // a cut which isn’t moving forward in time is reported and left out
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [{ "cuts": [{ "in_time": 0, "out_time": 4, "inst_time": 0, "clip": "a", "speed": 0 }] }]
+  }"#).unwrap();
+  assert_eq!(manifest.lint(), vec![Lint::InvalidSpeed { track: 0, cut: 0, speed: 0. }]);
+
+  let mut mapping = HashMap::new();
+  mapping.insert("a".to_owned(), &clip);
+  let timeline = Timeline::from_manifest(&manifest, &mapping, &TransitionRegistry::new());
+  assert!(timeline.tracks()[0].cuts().is_empty());
+}
+
+#[test]
+fn timeline_queries() {
+  let clips = ColorClips::new();
+  let mapping = clips.mapping();
+
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [
+      { "cuts": [{ "in_time": 0, "out_time": 4, "inst_time": 1, "clip": "red" }] },
+      { "cuts": [{ "in_time": 0, "out_time": 2, "inst_time": 3, "clip": "blue", "speed": 0.5 }] }
+    ]
+  }"#).unwrap();
+  let timeline = Timeline::from_manifest(&manifest, &mapping, &TransitionRegistry::new());
+
+  let active: Vec<_> = timeline.active_cuts(4.).into_iter().map(|(track, cut)| (track, cut.name.clone().unwrap())).collect();
+  assert_eq!(active, vec![(0, "red".to_owned()), (1, "blue".to_owned())]);
+  assert!(timeline.active_cuts(0.5).is_empty());
+
+  assert_eq!(timeline.next_edit_point(0.), Some(1.));
+  assert_eq!(timeline.next_edit_point(3.), Some(5.));
+  assert_eq!(timeline.next_edit_point(7.), None);
+  assert_eq!(timeline.previous_edit_point(5.), Some(3.));
+  assert_eq!(timeline.previous_edit_point(1.), None);
+
+  // jumping to the next cut lands on a frame that plays
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [
+      { "cuts": [
+        { "in_time": 0, "out_time": 2, "inst_time": 0, "clip": "red" },
+        { "in_time": 0, "out_time": 2, "inst_time": 2, "clip": "blue" }
+      ] }
+    ]
+  }"#).unwrap();
+  let cuts = Timeline::from_manifest(&manifest, &mapping, &TransitionRegistry::new());
+  let t = cuts.next_edit_point(0.).unwrap();
+  assert_eq!(t, 2.);
+  assert_eq!(cuts.active_cuts(t).len(), 1);
+  match cuts.play(t) {
+    Played::Resolved(Node::Color(color)) => assert_eq!(color.z, 1.),
+    _ => panic!("the second cut should play at the edit point")
+  }
+
+  assert_eq!(timeline.duration(), 7.);
+  assert_eq!(timeline.tracks()[1].bounds(), Some((3., 7.)));
+  assert_eq!(Track::new().bounds(), None);
+}
+
+#[test]
+fn interchange() {
+  let manifest: TimelineManifest = serde_json::from_str(r#"{
+    "tracks": [
+      { "cuts": [
+        { "in_time": 1, "out_time": 3, "inst_time": 0.5, "clip": "red" },
+        { "in_time": 0, "out_time": 4, "inst_time": 2.5, "clip": "blue", "speed": 2, "reverse": true }
+      ] },
+      { "cuts": [{ "in_time": 0, "out_time": 1, "inst_time": 0, "clip": "green" }], "muted": true }
+    ]
+  }"#).unwrap();
+
+  let edl = to_edl(&manifest.tracks[0], "demo", 24.into(), "01:00:00:00").unwrap();
+  assert!(edl.contains("001  red      V     C        00:00:01:00 00:00:03:00 01:00:00:12 01:00:02:12"));
+  assert!(edl.contains("* FROM CLIP NAME: blue"));
+  assert_eq!(from_edl(&edl, 24.into(), "01:00:00:00").unwrap(), manifest.tracks[0]);
+  assert!(from_edl(&edl, 24.into(), "02:00:00:00").is_err());
+  assert!(from_edl("001  red V C 00:00:01 00:00:03:00 00:00:00:12 00:00:02:12", 24.into(), "00:00:00:00").is_err());
+  assert!(from_edl(&edl, 0.into(), "01:00:00:00").is_err());
+  assert!(to_edl(&manifest.tracks[0], "demo", 0.into(), "01:00:00:00").is_err());
+
+  // at 23.976 fps, timecodes count 24 frames per second, a little more than a second
+  let rate = FrameRate::new(24000, 1001);
+  assert_eq!(rate.timecode_base(), 24);
+  let track = from_edl("001  red V C 00:01:00:00 00:01:01:00 00:00:00:00 00:00:01:00", rate, "00:00:00:00").unwrap();
+  assert!((track.cuts[0].in_time.secs().unwrap() - 60.06).abs() < 1e-9);
+
+  // events with video only or both audio and video are imported, audio events are skipped
+  let edl = "001  red      AA/V  C        00:00:01:00 00:00:03:00 00:00:00:12 00:00:02:12\n\
+             002  music    A     C        00:00:00:00 00:00:02:00 00:00:00:00 00:00:02:00\n\
+             003  blue     B     C        00:00:00:00 00:00:01:00 00:00:02:12 00:00:03:12\n";
+  let clips: Vec<_> = from_edl(edl, 24.into(), "00:00:00:00").unwrap().cuts.into_iter().map(|cut| cut.clip).collect();
+  assert_eq!(clips, vec!["red".to_owned(), "blue".to_owned()]);
+
+  let otio = to_otio(&manifest, "demo", 24.into()).unwrap();
+  assert_eq!(from_otio(&otio).unwrap(), manifest);
+  assert!(to_otio(&manifest, "demo", 0.into()).is_err());
+
+  let otio = to_otio(&manifest, "demo", FrameRate::new(30000, 1001)).unwrap();
+  let cut = from_otio(&otio).unwrap().tracks[0].cuts[1].clone();
+  assert!((cut.inst_time.secs().unwrap() - 2.5).abs() < 1e-9);
+
+  // a clip without a source range plays its whole media
+  let otio = r#"{
+    "OTIO_SCHEMA": "Timeline.1",
+    "tracks": {
+      "OTIO_SCHEMA": "Stack.1",
+      "children": [{
+        "OTIO_SCHEMA": "Track.1",
+        "children": [{
+          "OTIO_SCHEMA": "Clip.1",
+          "name": "red",
+          "media_reference": {
+            "OTIO_SCHEMA": "ExternalReference.1",
+            "available_range": {
+              "OTIO_SCHEMA": "TimeRange.1",
+              "start_time": { "OTIO_SCHEMA": "RationalTime.1", "value": 24, "rate": 24 },
+              "duration": { "OTIO_SCHEMA": "RationalTime.1", "value": 48, "rate": 24 }
+            }
+          }
+        }]
+      }]
+    }
+  }"#;
+  let cut = from_otio(otio).unwrap().tracks[0].cuts[0].clone();
+  assert_eq!((cut.in_time, cut.out_time), (TimeSpec::Secs(1.), TimeSpec::Secs(3.)));
+  assert!(from_otio(&otio.replace("available_range", "unknown_range")).is_err());
+}